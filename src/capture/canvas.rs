@@ -2,12 +2,14 @@ use std::ops::Mul;
 
 use iced::{
     Color,
+    Font,
     Pixels,
     Point,
     Radians,
     Rectangle,
     Renderer,
     Size,
+    Vector,
     alignment::Vertical,
     widget::{
         Action,
@@ -27,6 +29,7 @@ use iced::{
                 arc::Elliptical,
             },
         },
+        image::Handle,
         text::{
             Alignment,
             LineHeight,
@@ -38,18 +41,45 @@ use crate::{
     capture::{
         Capture,
         Message,
+        crop::{
+            self,
+            CropState,
+            Grip,
+        },
         draw::{
+            COUNTER_RADIUS_FACTOR,
             DrawElement,
             FONT_SIZE_FACTOR,
             STROKE_WIDHT_FACTOR,
             Tool,
         },
+        line::LineShape,
+        loupe,
+        magnify::{
+            BORDER_WIDTH,
+            Lens,
+            magnified,
+        },
         mode::Mode,
+        redact::redacted,
+        select::{
+            GRAB_TOLERANCE,
+            HANDLE_SIZE,
+            handles,
+        },
+        spotlight::shade,
+        text::{
+            TextBackground,
+            TextLayout,
+        },
     },
     consts::MEDIUM_FONT,
     theme::Theme,
 };
 
+/// Edge of the color preview next to the cursor while sampling.
+const EYEDROPPER_SWATCH_SIZE: f32 = 24.0;
+
 impl Program<Message, Theme> for Capture {
     type State = ();
 
@@ -62,9 +92,9 @@ impl Program<Message, Theme> for Capture {
     ) -> Option<Action<Message>> {
         match event {
             iced::Event::Mouse(event) => match event {
-                iced::mouse::Event::CursorMoved { position } => {
-                    Some(Action::publish(Message::MouseMoved(*position)))
-                }
+                iced::mouse::Event::CursorMoved { position } => Some(Action::publish(
+                    Message::MouseMoved(Point::new(position.x / self.zoom, position.y / self.zoom)),
+                )),
                 iced::mouse::Event::ButtonPressed(iced::mouse::Button::Left) => {
                     Some(Action::publish(Message::MousePressed))
                 }
@@ -73,6 +103,9 @@ impl Program<Message, Theme> for Capture {
                 }
                 _ => None,
             },
+            iced::Event::Keyboard(iced::keyboard::Event::ModifiersChanged(modifiers)) => {
+                Some(Action::publish(Message::ModifiersChanged(*modifiers)))
+            }
             _ => None,
         }
     }
@@ -83,9 +116,16 @@ impl Program<Message, Theme> for Capture {
         renderer: &Renderer,
         _theme: &Theme,
         bounds: Rectangle,
-        _cursor: iced::advanced::mouse::Cursor,
+        cursor: iced::advanced::mouse::Cursor,
     ) -> Vec<Geometry<Renderer>> {
+        // Shapes are in logical pixels of the screenshot, the window may show it smaller
+        let screen = self.logical_size();
+
         let mut frame = Frame::new(renderer, bounds.size());
+        frame.scale(self.zoom);
+
+        // Stays at the window's scale, like the cursor
+        let mut cursor_frame = Frame::new(renderer, bounds.size());
 
         let mut overlay_frame = Frame::new(renderer, bounds.size());
         overlay_frame.fill_rectangle(
@@ -95,22 +135,53 @@ impl Program<Message, Theme> for Capture {
         );
 
         let shapes_frame = self.cache.draw(renderer, bounds.size(), |frame| {
+            frame.scale(self.zoom);
+
+            // Effects alter the screenshot, so they stay below every shape
             self.elements
                 .iter()
-                .for_each(|shape| draw_shape(frame, shape, false));
+                .for_each(|shape| self.draw_effects(frame, shape));
+
+            let (width, height) = self.screenshot.dimensions();
+            if let Some(shade) = shade(
+                &self.elements,
+                width,
+                height,
+                Point::ORIGIN,
+                self.scale_factor,
+            ) {
+                let handle = Handle::from_rgba(width, height, shade.into_raw());
+                frame.draw_image(Rectangle::with_size(screen), &handle);
+            }
+
+            let mut number = 0;
+
+            self.elements.iter().for_each(|shape| {
+                if let Tool::Counter { .. } = shape.tool {
+                    number += 1;
+                }
+                draw_shape(frame, shape, number, false)
+            });
         });
 
         match &self.mode {
             Mode::Draw { element: shape, .. } => {
                 if self.mode.allows_drawing() {
-                    draw_shape(&mut frame, shape, true);
+                    let number = self
+                        .elements
+                        .iter()
+                        .filter(|element| matches!(element.tool, Tool::Counter { .. }))
+                        .count()
+                        + 1;
+
+                    self.draw_element(&mut frame, shape, number as u32, true);
                 }
             }
             Mode::Crop {
                 top_left,
                 bottom_right,
                 size,
-                ..
+                state,
             } => {
                 let overlay = Fill::from(Color::from_rgba(0.0, 0.0, 0.0, 0.5));
 
@@ -126,15 +197,15 @@ impl Program<Message, Theme> for Capture {
                     Point::new(0.0, 0.0),
                     Size {
                         height: top_left.y,
-                        width: bounds.width,
+                        width: screen.width,
                     },
                     overlay,
                 );
                 frame.fill_rectangle(
                     Point::new(0.0, bottom_right.y),
                     Size {
-                        height: bounds.height - bottom_right.y,
-                        width: bounds.width,
+                        height: screen.height - bottom_right.y,
+                        width: screen.width,
                     },
                     overlay,
                 );
@@ -150,7 +221,7 @@ impl Program<Message, Theme> for Capture {
                     Point::new(bottom_right.x, top_left.y),
                     Size {
                         height: bottom_right.y - top_left.y,
-                        width: bounds.width - bottom_right.x,
+                        width: screen.width - bottom_right.x,
                     },
                     overlay,
                 );
@@ -184,13 +255,77 @@ impl Program<Message, Theme> for Capture {
                 };
 
                 frame.stroke(&selection, dashed_stroke);
+
+                if let CropState::Area | CropState::Adjusting { .. } = state {
+                    crop::handles(Rectangle::new(*top_left, *size))
+                        .into_iter()
+                        .for_each(|handle| draw_handle(&mut frame, handle));
+                }
             }
+            Mode::Select {
+                selected: Some(index),
+                ..
+            } => {
+                if let Some(element) = self.elements.get(*index) {
+                    let bounds = element.bounds();
+                    let stroke = Stroke::default().with_color(Color::WHITE);
+
+                    frame.stroke_rectangle(bounds.position(), bounds.size(), stroke);
+
+                    if element.tool.is_resizable() {
+                        handles(bounds)
+                            .into_iter()
+                            .for_each(|handle| draw_handle(&mut frame, handle));
+                    }
+
+                    // Round, unlike the resize handles
+                    if let Some(control) = element.control() {
+                        let handle = Path::circle(control, HANDLE_SIZE / 2.0);
+
+                        frame.fill(&handle, Color::WHITE);
+                        frame.stroke(&handle, stroke.with_color(Color::BLACK));
+                    }
+                }
+            }
+            Mode::Select { selected: None, .. } => {}
+        }
+
+        // Hidden while the cursor is over the toolbar
+        if let Mode::Crop { .. } = self.mode
+            && let Some(position) = cursor.position_in(bounds)
+        {
+            loupe::draw(
+                &mut cursor_frame,
+                &self.screenshot,
+                position,
+                self.scale_factor / self.zoom,
+            );
+        }
+
+        if self.eyedropper
+            && let Some(color) = self.color_at(self.cursor_position)
+        {
+            let offset = Vector::new(EYEDROPPER_SWATCH_SIZE / 2.0, EYEDROPPER_SWATCH_SIZE / 2.0);
+            let swatch = Path::rectangle(
+                Point::new(
+                    self.cursor_position.x * self.zoom,
+                    self.cursor_position.y * self.zoom,
+                ) + offset,
+                Size::new(EYEDROPPER_SWATCH_SIZE, EYEDROPPER_SWATCH_SIZE),
+            );
+
+            cursor_frame.fill(&swatch, Color::from(color));
+            cursor_frame.stroke(
+                &swatch,
+                Stroke::default().with_width(2.0).with_color(Color::WHITE),
+            );
         }
 
         vec![
             overlay_frame.into_geometry(),
             shapes_frame,
             frame.into_geometry(),
+            cursor_frame.into_geometry(),
         ]
     }
 
@@ -200,11 +335,45 @@ impl Program<Message, Theme> for Capture {
         bounds: Rectangle,
         cursor: iced::advanced::mouse::Cursor,
     ) -> iced::mouse::Interaction {
-        if cursor.is_over(bounds) {
-            if let Mode::Draw { element, .. } = &self.mode
-                && element.tool.is_text_tool()
-            {
-                return iced::mouse::Interaction::Text;
+        if let Some(position) = cursor.position_in(bounds) {
+            let position = Point::new(position.x / self.zoom, position.y / self.zoom);
+
+            match &self.mode {
+                Mode::Draw { element, .. } if element.tool.is_text_tool() => {
+                    return iced::mouse::Interaction::Text;
+                }
+                Mode::Crop {
+                    top_left,
+                    size,
+                    state,
+                    ..
+                } => match state {
+                    CropState::Adjusting { grip, .. } => {
+                        return match grip.interaction() {
+                            iced::mouse::Interaction::Grab => iced::mouse::Interaction::Grabbing,
+                            interaction => interaction,
+                        };
+                    }
+                    CropState::Area => {
+                        if let Some(grip) = Grip::at(Rectangle::new(*top_left, *size), position) {
+                            return grip.interaction();
+                        }
+                    }
+                    _ => {}
+                },
+                Mode::Select { state, .. } => {
+                    if !state.is_idle() {
+                        return iced::mouse::Interaction::Grabbing;
+                    }
+                    if self
+                        .elements
+                        .iter()
+                        .any(|element| element.contains(position, GRAB_TOLERANCE))
+                    {
+                        return iced::mouse::Interaction::Grab;
+                    }
+                }
+                _ => {}
             }
             return iced::mouse::Interaction::Crosshair;
         }
@@ -212,12 +381,59 @@ impl Program<Message, Theme> for Capture {
     }
 }
 
-fn draw_shape(frame: &mut Frame, element: &DrawElement, guide: bool) {
+/// Draws a square resize handle centered on the point.
+fn draw_handle(frame: &mut Frame, handle: Point) {
+    let top_left = Point::new(handle.x - HANDLE_SIZE / 2.0, handle.y - HANDLE_SIZE / 2.0);
+    let size = Size::new(HANDLE_SIZE, HANDLE_SIZE);
+
+    frame.fill_rectangle(top_left, size, Color::WHITE);
+    frame.stroke_rectangle(top_left, size, Stroke::default().with_color(Color::BLACK));
+}
+
+impl Capture {
+    /// Draws the element, `number` being shown by counters.
+    fn draw_element(&self, frame: &mut Frame, element: &DrawElement, number: u32, guide: bool) {
+        self.draw_effects(frame, element);
+        draw_shape(frame, element, number, guide);
+    }
+
+    /// Draws the parts of the element taken from the screenshot.
+    fn draw_effects(&self, frame: &mut Frame, element: &DrawElement) {
+        if let Some((x, y, redacted)) =
+            redacted(&self.screenshot, element, Point::ORIGIN, self.scale_factor)
+        {
+            let bounds = Rectangle::new(
+                Point::new(x as f32, y as f32),
+                Size::new(redacted.width() as f32, redacted.height() as f32),
+            ) * (1.0 / self.scale_factor);
+            let handle =
+                Handle::from_rgba(redacted.width(), redacted.height(), redacted.into_raw());
+
+            frame.draw_image(bounds, &handle);
+        }
+
+        if let Some((x, y, magnified)) =
+            magnified(&self.screenshot, element, Point::ORIGIN, self.scale_factor)
+        {
+            let bounds = Rectangle::new(
+                Point::new(x as f32, y as f32),
+                Size::new(magnified.width() as f32, magnified.height() as f32),
+            ) * (1.0 / self.scale_factor);
+            let handle =
+                Handle::from_rgba(magnified.width(), magnified.height(), magnified.into_raw());
+
+            frame.draw_image(bounds, &handle);
+        }
+    }
+}
+
+fn draw_shape(frame: &mut Frame, element: &DrawElement, number: u32, guide: bool) {
     let tool = element.tool.clone();
     let color = element.color.into();
     let stroke = Stroke::default()
         .with_width(element.size.mul(STROKE_WIDHT_FACTOR) as f32)
         .with_color(color);
+    let dash = element.line_style.dash.pattern(stroke.width);
     match tool {
         Tool::Rectangle {
             top_left,
@@ -269,51 +485,183 @@ fn draw_shape(frame: &mut Frame, element: &DrawElement, guide: bool) {
                 .for_each(|point| builder.line_to(*point));
             let path = builder.build();
 
-            frame.stroke(&path, stroke);
-        }
-        Tool::Line { start, end } => {
-            let path = Path::line(start, end);
-            frame.stroke(&path, stroke);
+            frame.stroke(
+                &path,
+                Stroke {
+                    line_dash: LineDash {
+                        segments: &dash,
+                        offset: 0,
+                    },
+                    ..stroke
+                },
+            );
         }
-        Tool::Arrow {
-            start,
-            end,
-            right,
-            left,
-        } => {
+        Tool::Line { .. } | Tool::Arrow { .. } => {
+            let Some(line) = LineShape::of(element) else {
+                return;
+            };
+
             let mut builder = Builder::new();
-            builder.move_to(start);
-            builder.line_to(end);
-            builder.move_to(right);
-            builder.line_to(end);
-            builder.line_to(left);
+            builder.move_to(line.start);
+            match line.control {
+                Some(control) => builder.quadratic_curve_to(control, line.end),
+                None => builder.line_to(line.end),
+            }
             let path = builder.build();
-            frame.stroke(&path, stroke);
+
+            frame.stroke(
+                &path,
+                Stroke {
+                    line_dash: LineDash {
+                        segments: &dash,
+                        offset: 0,
+                    },
+                    ..stroke
+                },
+            );
+
+            // Heads stay solid whatever the line is
+            line.heads.into_iter().for_each(|[right, tip, left]| {
+                let mut builder = Builder::new();
+                builder.move_to(right);
+                builder.line_to(tip);
+                builder.line_to(left);
+                if element.line_style.filled_head {
+                    builder.close();
+                }
+                let head = builder.build();
+
+                if element.line_style.filled_head {
+                    frame.fill(&head, color);
+                }
+                frame.stroke(&head, stroke);
+            });
         }
-        Tool::Text {
-            anchor_point: mid_point,
-            text,
+        Tool::Pixelate { top_left, size, .. } | Tool::Blur { top_left, size, .. } => {
+            if guide {
+                frame.stroke_rectangle(top_left, size, Stroke::default().with_color(Color::WHITE));
+            }
+        }
+        // The shade is drawn for all spotlights at once, once they are added
+        Tool::Spotlight {
+            top_left,
+            size,
+            elliptical,
+            ..
         } => {
-            let font_size = element.size.mul(FONT_SIZE_FACTOR);
+            if guide {
+                let path = match elliptical {
+                    true => {
+                        let mut builder = Builder::new();
+                        builder.ellipse(Elliptical {
+                            center: Rectangle::new(top_left, size).center(),
+                            radii: Vector::new(size.width / 2.0, size.height / 2.0),
+                            rotation: Radians(0.0),
+                            start_angle: Radians(0.0),
+                            end_angle: Radians(360.0),
+                        });
+                        builder.build()
+                    }
+                    false => Path::rectangle(top_left, size),
+                };
+
+                frame.stroke(&path, Stroke::default().with_color(Color::WHITE));
+            }
+        }
+        Tool::Text { anchor_point, text } => {
+            let layout = TextLayout::new(
+                anchor_point,
+                &text,
+                element.size.mul(FONT_SIZE_FACTOR) as f32,
+            );
+            let bounds = layout.bounds;
 
-            let top_left = Point::new(mid_point.x, mid_point.y - (font_size / 2) as f32);
+            match element.text_style.background {
+                TextBackground::None => {}
+                TextBackground::Filled => {
+                    frame.fill_rectangle(
+                        bounds.position(),
+                        bounds.size(),
+                        Color::from(element.color.contrasting()),
+                    );
+                }
+                TextBackground::Outline => {
+                    frame.stroke_rectangle(bounds.position(), bounds.size(), stroke);
+                }
+            }
 
             if guide {
                 frame.stroke_rectangle(
-                    top_left,
-                    Size::new(frame.width() - mid_point.x, font_size as f32),
+                    bounds.position(),
+                    bounds.size(),
                     Stroke::default().with_color(Color::WHITE),
                 );
             }
 
+            fill_text_lines(frame, layout, element.text_style.font(), color);
+        }
+        Tool::Callout {
+            target,
+            anchor_point,
+            text,
+        } => {
+            let layout = TextLayout::new(
+                anchor_point,
+                &text,
+                element.size.mul(FONT_SIZE_FACTOR) as f32,
+            );
+
+            let [base_start, tip, base_end] = layout.tail(target);
+            let tail = Path::new(|builder| {
+                builder.move_to(base_start);
+                builder.line_to(tip);
+                builder.line_to(base_end);
+                builder.close();
+            });
+            let bubble = Path::rounded_rectangle(
+                layout.bounds.position(),
+                layout.bounds.size(),
+                layout.corner_radius().into(),
+            );
+            let fill = Color::from(element.color.contrasting());
+
+            // Outlines go first, so the fills hide them where the tail joins the box
+            frame.stroke(&tail, stroke);
+            frame.stroke(&bubble, stroke);
+            frame.fill(&tail, fill);
+            frame.fill(&bubble, fill);
+
+            fill_text_lines(frame, layout, element.text_style.font(), color);
+        }
+        Tool::Magnifier { .. } => {
+            let Some(lens) = Lens::of(element) else {
+                return;
+            };
+            let outline = |area: Rectangle| match lens.circular {
+                true => Path::circle(area.center(), area.width / 2.0),
+                false => Path::rectangle(area.position(), area.size()),
+            };
+            let stroke = stroke.with_width(BORDER_WIDTH);
+
+            frame.stroke(&outline(lens.source), stroke);
+            if let Some((start, end)) = lens.connector() {
+                frame.stroke(&Path::line(start, end), stroke);
+            }
+            frame.stroke(&outline(lens.lens), stroke);
+        }
+        Tool::Counter { center } => {
+            let radius = element.size.mul(COUNTER_RADIUS_FACTOR) as f32;
+
+            frame.fill(&Path::circle(center, radius), color);
+
             let text = Text {
-                content: text,
-                position: top_left,
-                size: Pixels(font_size as f32),
-                color,
+                content: number.to_string(),
+                position: center,
+                size: Pixels(radius),
+                color: element.color.contrasting().into(),
                 font: MEDIUM_FONT,
-                align_x: Alignment::Left,
-                align_y: Vertical::Top,
+                align_x: Alignment::Center,
+                align_y: Vertical::Center,
                 line_height: LineHeight::Relative(1.0),
                 ..Default::default()
             };
@@ -322,3 +670,19 @@ fn draw_shape(frame: &mut Frame, element: &DrawElement, guide: bool) {
         }
     }
 }
+
+fn fill_text_lines(frame: &mut Frame, layout: TextLayout, font: Font, color: Color) {
+    layout.lines.into_iter().for_each(|(line, position)| {
+        frame.fill_text(Text {
+            content: line.to_string(),
+            position,
+            size: Pixels(layout.font_size),
+            color,
+            font,
+            align_x: Alignment::Left,
+            align_y: Vertical::Center,
+            line_height: LineHeight::Relative(1.0),
+            ..Default::default()
+        });
+    });
+}
@@ -1,8 +1,27 @@
-use std::rc::Rc;
+use std::{
+    fmt::Display,
+    rc::Rc,
+};
 
-use iced::Point;
+use iced::{
+    Point,
+    Rectangle,
+    Size,
+    Vector,
+    mouse::Interaction,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
 
-use crate::capture::CapturedWindow;
+use crate::capture::{
+    CapturedWindow,
+    select::{
+        GRAB_TOLERANCE,
+        HANDLE_SIZE,
+    },
+};
 
 #[derive(Debug, Default)]
 pub enum CropState {
@@ -12,6 +31,14 @@ pub enum CropState {
     InProgress {
         start: Point,
         end: Point,
+        /// Area selected before, restored on undo.
+        previous: Option<Rectangle>,
+    },
+    /// Moving or resizing the selected area.
+    Adjusting {
+        grip: Grip,
+        start: Point,
+        original: Rectangle,
     },
     Area,
     None,
@@ -19,6 +46,313 @@ pub enum CropState {
 
 impl CropState {
     pub fn is_idle(&self) -> bool {
-        !matches!(self, Self::InProgress { .. })
+        !matches!(self, Self::InProgress { .. } | Self::Adjusting { .. })
+    }
+}
+
+/// Edges of the selected area following the cursor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Grip {
+    pub left: bool,
+    pub top: bool,
+    pub right: bool,
+    pub bottom: bool,
+}
+
+impl Grip {
+    /// Grip moving the whole area.
+    pub const INSIDE: Self = Self {
+        left: true,
+        top: true,
+        right: true,
+        bottom: true,
+    };
+
+    /// Grip under the cursor, edges taking precedence over the inside.
+    pub fn at(area: Rectangle, cursor: Point) -> Option<Self> {
+        let reach = HANDLE_SIZE / 2.0 + GRAB_TOLERANCE;
+
+        if !area.expand(reach).contains(cursor) {
+            return None;
+        }
+
+        // On small areas, the closest of two opposite edges is grabbed
+        let closest = |value: f32, start: f32, end: f32| {
+            let (to_start, to_end) = ((value - start).abs(), (value - end).abs());
+            (
+                to_start <= reach && to_start <= to_end,
+                to_end <= reach && to_end < to_start,
+            )
+        };
+        let (left, right) = closest(cursor.x, area.x, area.x + area.width);
+        let (top, bottom) = closest(cursor.y, area.y, area.y + area.height);
+
+        match left || top || right || bottom {
+            true => Some(Self {
+                left,
+                top,
+                right,
+                bottom,
+            }),
+            false => Some(Self::INSIDE),
+        }
+    }
+
+    /// Area after dragging the grip by `offset`, kept within `screen`.
+    pub fn drag(self, area: Rectangle, offset: Vector, screen: Size) -> Rectangle {
+        if self == Self::INSIDE {
+            return move_within(area, offset, screen);
+        }
+
+        let edge = |value: f32, grabbed: bool, offset: f32, max: f32| match grabbed {
+            true => (value + offset).clamp(0.0, max),
+            false => value,
+        };
+        let (left, right) = (
+            edge(area.x, self.left, offset.x, screen.width),
+            edge(area.x + area.width, self.right, offset.x, screen.width),
+        );
+        let (top, bottom) = (
+            edge(area.y, self.top, offset.y, screen.height),
+            edge(area.y + area.height, self.bottom, offset.y, screen.height),
+        );
+
+        // Dragging an edge past the opposite one flips the area
+        Rectangle::new(
+            Point::new(left.min(right), top.min(bottom)),
+            Size::new((right - left).abs(), (bottom - top).abs()),
+        )
+    }
+
+    pub fn interaction(self) -> Interaction {
+        match self {
+            Self::INSIDE => Interaction::Grab,
+            Self {
+                left: false,
+                right: false,
+                ..
+            } => Interaction::ResizingVertically,
+            Self {
+                top: false,
+                bottom: false,
+                ..
+            } => Interaction::ResizingHorizontally,
+            Self { left, top, .. } if left == top => Interaction::ResizingDiagonallyDown,
+            _ => Interaction::ResizingDiagonallyUp,
+        }
+    }
+}
+
+/// Corners and middles of the edges of the area, which act as resize handles.
+pub fn handles(area: Rectangle) -> [Point; 8] {
+    let (left, top) = (area.x, area.y);
+    let (right, bottom) = (area.x + area.width, area.y + area.height);
+    let center = area.center();
+
+    [
+        Point::new(left, top),
+        Point::new(center.x, top),
+        Point::new(right, top),
+        Point::new(right, center.y),
+        Point::new(right, bottom),
+        Point::new(center.x, bottom),
+        Point::new(left, bottom),
+        Point::new(left, center.y),
+    ]
+}
+
+/// Area moved by `offset`, stopping at the edges of `screen`.
+pub fn move_within(area: Rectangle, offset: Vector, screen: Size) -> Rectangle {
+    Rectangle::new(
+        Point::new(
+            (area.x + offset.x).clamp(0.0, (screen.width - area.width).max(0.0)),
+            (area.y + offset.y).clamp(0.0, (screen.height - area.height).max(0.0)),
+        ),
+        area.size(),
+    )
+}
+
+/// Size typed in the toolbar, kept as written while it is edited.
+#[derive(Debug, Clone)]
+pub struct SizeInput {
+    pub width: String,
+    pub height: String,
+}
+
+impl SizeInput {
+    /// Input showing the size in physical pixels.
+    pub fn of(size: Size, scale_factor: f32) -> Self {
+        let pixels = |length: f32| ((length * scale_factor).round() as u32).to_string();
+
+        Self {
+            width: pixels(size.width),
+            height: pixels(size.height),
+        }
+    }
+
+    /// Size typed, in logical pixels, if both dimensions are valid.
+    pub fn size(&self, scale_factor: f32) -> Option<Size> {
+        let pixels = |text: &str| text.trim().parse::<u32>().ok().filter(|pixels| *pixels > 0);
+
+        Some(Size::new(
+            pixels(&self.width)? as f32 / scale_factor,
+            pixels(&self.height)? as f32 / scale_factor,
+        ))
+    }
+}
+
+/// Shape forced onto areas selected by dragging.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum CropPreset {
+    #[default]
+    Free,
+    /// Width to height ratio, written `16:9`.
+    Ratio(u32, u32),
+    /// Exact size in physical pixels, written `1280x720`.
+    Size(u32, u32),
+}
+
+impl CropPreset {
+    pub const DEFAULTS: [CropPreset; 5] = [
+        Self::Ratio(16, 9),
+        Self::Ratio(4, 3),
+        Self::Ratio(1, 1),
+        Self::Size(1280, 720),
+        Self::Size(1920, 1080),
+    ];
+
+    /// End of a drag from `start` to `end` following the preset.
+    pub fn constrain(self, start: Point, end: Point, scale_factor: f32) -> Point {
+        let offset = end - start;
+        let (width, height) = match self {
+            CropPreset::Free => return end,
+            CropPreset::Ratio(width, height) => {
+                let ratio = width as f32 / height as f32;
+
+                // The longer side of the drag sets the size
+                match offset.x.abs() / ratio > offset.y.abs() {
+                    true => (offset.x.abs(), offset.x.abs() / ratio),
+                    false => (offset.y.abs() * ratio, offset.y.abs()),
+                }
+            }
+            CropPreset::Size(width, height) => {
+                (width as f32 / scale_factor, height as f32 / scale_factor)
+            }
+        };
+
+        start + Vector::new(width * offset.x.signum(), height * offset.y.signum())
+    }
+
+    /// Whether areas of the preset fit on a screen of the given physical size.
+    pub fn fits(self, (width, height): (u32, u32)) -> bool {
+        match self {
+            CropPreset::Size(preset_width, preset_height) => {
+                preset_width <= width && preset_height <= height
+            }
+            _ => true,
+        }
+    }
+}
+
+impl Display for CropPreset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CropPreset::Free => write!(f, "Free"),
+            CropPreset::Ratio(width, height) => write!(f, "{width}:{height}"),
+            CropPreset::Size(width, height) => write!(f, "{width}x{height}"),
+        }
+    }
+}
+
+impl From<CropPreset> for String {
+    fn from(value: CropPreset) -> Self {
+        value.to_string()
+    }
+}
+
+impl TryFrom<String> for CropPreset {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let pair = |separator| {
+            let (width, height) = value.trim().split_once(separator)?;
+            let length = |text: &str| text.trim().parse::<u32>().ok().filter(|length| *length > 0);
+
+            Some((length(width)?, length(height)?))
+        };
+
+        match value.trim() {
+            "Free" => Some(CropPreset::Free),
+            _ => pair(':')
+                .map(|(width, height)| CropPreset::Ratio(width, height))
+                .or_else(|| pair('x').map(|(width, height)| CropPreset::Size(width, height))),
+        }
+        .ok_or_else(|| format!("Invalid crop preset: {value}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preset(value: &str) -> Result<CropPreset, String> {
+        CropPreset::try_from(value.to_string())
+    }
+
+    #[test]
+    fn parses_presets() {
+        assert_eq!(preset("Free"), Ok(CropPreset::Free));
+        assert_eq!(preset("16:9"), Ok(CropPreset::Ratio(16, 9)));
+        assert_eq!(preset("1280x720"), Ok(CropPreset::Size(1280, 720)));
+        assert_eq!(preset(" 4 : 3 "), Ok(CropPreset::Ratio(4, 3)));
+        assert_eq!(preset(" 1920 x 1080 "), Ok(CropPreset::Size(1920, 1080)));
+    }
+
+    #[test]
+    fn rejects_invalid_presets() {
+        assert!(preset("0:9").is_err());
+        assert!(preset("16:0").is_err());
+        assert!(preset("0x720").is_err());
+        assert!(preset("16:").is_err());
+        assert!(preset("-16:9").is_err());
+        assert!(preset("1280*720").is_err());
+        assert!(preset("free").is_err());
+        assert!(preset("").is_err());
+    }
+
+    #[test]
+    fn displays_presets() {
+        for value in CropPreset::DEFAULTS.into_iter().chain([CropPreset::Free]) {
+            assert_eq!(preset(&value.to_string()), Ok(value));
+        }
+
+        assert_eq!(CropPreset::Ratio(16, 9).to_string(), "16:9");
+        assert_eq!(CropPreset::Size(1280, 720).to_string(), "1280x720");
+    }
+
+    #[test]
+    fn constrains_drags() {
+        let start = Point::new(100.0, 100.0);
+
+        assert_eq!(
+            CropPreset::Ratio(16, 9).constrain(start, Point::new(260.0, 120.0), 1.0),
+            Point::new(260.0, 190.0)
+        );
+        assert_eq!(
+            CropPreset::Size(1280, 720).constrain(start, Point::new(90.0, 110.0), 2.0),
+            Point::new(-540.0, 460.0)
+        );
+        assert_eq!(
+            CropPreset::Free.constrain(start, Point::new(90.0, 110.0), 2.0),
+            Point::new(90.0, 110.0)
+        );
+    }
+
+    #[test]
+    fn fits_screen() {
+        assert!(CropPreset::Size(1920, 1080).fits((1920, 1080)));
+        assert!(!CropPreset::Size(1920, 1080).fits((1280, 1024)));
+        assert!(CropPreset::Ratio(16, 9).fits((1, 1)));
     }
 }
@@ -1,31 +1,71 @@
 use iced::{
     Point,
+    Rectangle,
     Size,
     Vector,
 };
+use serde::{
+    Deserialize,
+    Serialize,
+};
 
-use crate::consts::{
-    ARROW_ICON,
-    FILLED_ELLIPSE_ICON,
-    FILLED_RECTANGLE_ICON,
-    FREE_HAND_ICON,
-    HIGHLIGHTER_ICON,
-    HOLLOW_ELLIPSE_ICON,
-    HOLLOW_RECTANGLE_ICON,
-    LINE_ICON,
-    TEXT_ICON,
+use crate::{
+    capture::{
+        line::{
+            LineShape,
+            LineStyle,
+            bend,
+        },
+        magnify::Lens,
+        project::{
+            PointDef,
+            SizeDef,
+            VectorDef,
+            points,
+        },
+        redact::Effect,
+        text::{
+            TextLayout,
+            TextStyle,
+        },
+    },
+    consts::{
+        ARROW_ICON,
+        BLUR_ICON,
+        CALLOUT_ICON,
+        COUNTER_ICON,
+        FILLED_ELLIPSE_ICON,
+        FILLED_RECTANGLE_ICON,
+        FREE_HAND_ICON,
+        HIGHLIGHTER_ICON,
+        HOLLOW_ELLIPSE_ICON,
+        HOLLOW_RECTANGLE_ICON,
+        LINE_ICON,
+        MAGNIFIER_ICON,
+        PIXELATE_ICON,
+        ROUND_SPOTLIGHT_ICON,
+        SPOTLIGHT_ICON,
+        SQUARE_MAGNIFIER_ICON,
+        TEXT_ICON,
+    },
 };
 
 pub const STROKE_WIDHT_FACTOR: u32 = 2;
 pub const FONT_SIZE_FACTOR: u32 = 12;
+pub const COUNTER_RADIUS_FACTOR: u32 = 6;
+pub const LENS_RADIUS_FACTOR: u32 = 20;
 
 pub type DrawElements = Vec<DrawElement>;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DrawElement {
     pub tool: Tool,
     pub color: ToolColor,
     pub size: u32,
+    #[serde(default)]
+    pub text_style: TextStyle,
+    #[serde(default)]
+    pub line_style: LineStyle,
 }
 
 impl Default for DrawElement {
@@ -34,41 +74,230 @@ impl Default for DrawElement {
             tool: Tool::default(),
             color: ToolColor::default(),
             size: 3,
+            text_style: TextStyle::default(),
+            line_style: LineStyle::default(),
+        }
+    }
+}
+
+impl DrawElement {
+    /// Smallest rectangle containing the drawn element.
+    pub fn bounds(&self) -> Rectangle {
+        if let Some(lens) = Lens::of(self) {
+            return lens.source.union(&lens.lens);
+        }
+
+        if let Some(line) = LineShape::of(self) {
+            let mut points = line.points();
+            points.extend(line.heads.into_iter().flatten());
+
+            return bounding_box(&points);
+        }
+
+        match &self.tool {
+            Tool::Text { anchor_point, text } => {
+                TextLayout::new(*anchor_point, text, (self.size * FONT_SIZE_FACTOR) as f32).bounds
+            }
+            Tool::Callout {
+                target,
+                anchor_point,
+                text,
+            } => TextLayout::new(*anchor_point, text, (self.size * FONT_SIZE_FACTOR) as f32)
+                .bounds
+                .union(&Rectangle::new(*target, Size::ZERO)),
+            Tool::Counter { center } => {
+                let radius = (self.size * COUNTER_RADIUS_FACTOR) as f32;
+
+                Rectangle::new(
+                    Point::new(center.x - radius, center.y - radius),
+                    Size::new(radius * 2.0, radius * 2.0),
+                )
+            }
+            tool => bounding_box(&tool.points()),
         }
     }
+
+    /// Control point of curved lines and arrows.
+    pub fn control(&self) -> Option<Point> {
+        LineShape::of(self).and_then(|line| line.control)
+    }
+
+    /// Whether the point lies on the element, within `tolerance`.
+    pub fn contains(&self, point: Point, tolerance: f32) -> bool {
+        let tolerance = tolerance + (self.size * STROKE_WIDHT_FACTOR) as f32 / 2.0;
+
+        let near_segments = |points: &[Point]| {
+            points
+                .windows(2)
+                .any(|segment| distance_to_segment(point, segment[0], segment[1]) <= tolerance)
+        };
+
+        match &self.tool {
+            Tool::Ellipse { center, radii, .. } => {
+                let (rx, ry) = (radii.x.abs() + tolerance, radii.y.abs() + tolerance);
+                let (dx, dy) = (point.x - center.x, point.y - center.y);
+
+                (dx * dx) / (rx * rx) + (dy * dy) / (ry * ry) <= 1.0
+            }
+            Tool::Counter { center } => {
+                center.distance(point) <= (self.size * COUNTER_RADIUS_FACTOR) as f32 + tolerance
+            }
+            Tool::Magnifier { .. } => Lens::of(self).is_some_and(|lens| {
+                lens.source.expand(tolerance).contains(point)
+                    || lens.lens.expand(tolerance).contains(point)
+                    || lens
+                        .connector()
+                        .is_some_and(|(start, end)| near_segments(&[start, end]))
+            }),
+            Tool::FreeHand { points } => near_segments(points),
+            Tool::Line { .. } | Tool::Arrow { .. } => LineShape::of(self).is_some_and(|line| {
+                near_segments(&line.points()) || line.heads.iter().any(|head| near_segments(head))
+            }),
+            Tool::Callout {
+                target,
+                anchor_point,
+                text,
+            } => {
+                let bounds =
+                    TextLayout::new(*anchor_point, text, (self.size * FONT_SIZE_FACTOR) as f32)
+                        .bounds;
+
+                bounds.expand(tolerance).contains(point)
+                    || near_segments(&[bounds.center(), *target])
+            }
+            Tool::Rectangle { .. }
+            | Tool::Pixelate { .. }
+            | Tool::Blur { .. }
+            | Tool::Spotlight { .. }
+            | Tool::Text { .. } => self.bounds().expand(tolerance).contains(point),
+        }
+    }
+}
+
+fn bounding_box(points: &[Point]) -> Rectangle {
+    let (min, max) = points.iter().fold(
+        (
+            Point::new(f32::INFINITY, f32::INFINITY),
+            Point::new(f32::NEG_INFINITY, f32::NEG_INFINITY),
+        ),
+        |(min, max), point| {
+            (
+                Point::new(min.x.min(point.x), min.y.min(point.y)),
+                Point::new(max.x.max(point.x), max.y.max(point.y)),
+            )
+        },
+    );
+
+    Rectangle::new(min, Size::new(max.x - min.x, max.y - min.y))
+}
+
+fn distance_to_segment(point: Point, start: Point, end: Point) -> f32 {
+    let segment = end - start;
+    let length_squared = segment.x * segment.x + segment.y * segment.y;
+
+    if length_squared == 0.0 {
+        return point.distance(start);
+    }
+
+    let to_point = point - start;
+    let t = ((to_point.x * segment.x + to_point.y * segment.y) / length_squared).clamp(0.0, 1.0);
+
+    point.distance(start + segment * t)
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Tool {
     Rectangle {
+        #[serde(with = "PointDef")]
         top_left: Point,
+        #[serde(with = "PointDef")]
         bottom_right: Point,
+        #[serde(with = "SizeDef")]
         size: Size,
         filled: bool,
         opaque: bool,
     },
     Ellipse {
+        #[serde(with = "PointDef")]
         center: Point,
+        #[serde(with = "VectorDef")]
         radii: Vector,
         filled: bool,
     },
     FreeHand {
+        #[serde(with = "points")]
         points: Vec<Point>,
     },
     Line {
+        #[serde(with = "PointDef")]
         start: Point,
+        #[serde(with = "PointDef")]
         end: Point,
+        /// Control point the line bends toward when curved.
+        #[serde(with = "PointDef", default)]
+        control: Point,
     },
     Arrow {
+        #[serde(with = "PointDef")]
         start: Point,
+        #[serde(with = "PointDef")]
         end: Point,
-        right: Point,
-        left: Point,
+        /// Control point the arrow bends toward when curved.
+        #[serde(with = "PointDef", default)]
+        control: Point,
+    },
+    Pixelate {
+        #[serde(with = "PointDef")]
+        top_left: Point,
+        #[serde(with = "PointDef")]
+        bottom_right: Point,
+        #[serde(with = "SizeDef")]
+        size: Size,
+    },
+    Blur {
+        #[serde(with = "PointDef")]
+        top_left: Point,
+        #[serde(with = "PointDef")]
+        bottom_right: Point,
+        #[serde(with = "SizeDef")]
+        size: Size,
+    },
+    /// Area left bright while everything around it is dimmed.
+    Spotlight {
+        #[serde(with = "PointDef")]
+        top_left: Point,
+        #[serde(with = "PointDef")]
+        bottom_right: Point,
+        #[serde(with = "SizeDef")]
+        size: Size,
+        elliptical: bool,
     },
     Text {
+        #[serde(with = "PointDef")]
+        anchor_point: Point,
+        text: String,
+    },
+    /// Text box with a tail pointing at `target`.
+    Callout {
+        #[serde(with = "PointDef")]
+        target: Point,
+        #[serde(with = "PointDef")]
         anchor_point: Point,
         text: String,
     },
+    /// Enlarged copy of the area around `source`, shown in a lens at `center`.
+    Magnifier {
+        #[serde(with = "PointDef")]
+        source: Point,
+        #[serde(with = "PointDef")]
+        center: Point,
+        circular: bool,
+    },
+    /// Numbered marker, numbered by its order among the counters.
+    Counter {
+        #[serde(with = "PointDef")]
+        center: Point,
+    },
 }
 
 impl Default for Tool {
@@ -109,14 +338,38 @@ impl PartialEq for Tool {
             (Self::FreeHand { .. }, Self::FreeHand { .. }) => true,
             (Self::Line { .. }, Self::Line { .. }) => true,
             (Self::Arrow { .. }, Self::Arrow { .. }) => true,
+            (Self::Pixelate { .. }, Self::Pixelate { .. }) => true,
+            (Self::Blur { .. }, Self::Blur { .. }) => true,
+            (
+                Self::Spotlight {
+                    elliptical: l_elliptical,
+                    ..
+                },
+                Self::Spotlight {
+                    elliptical: r_elliptical,
+                    ..
+                },
+            ) => l_elliptical == r_elliptical,
             (Self::Text { .. }, Self::Text { .. }) => true,
+            (Self::Callout { .. }, Self::Callout { .. }) => true,
+            (
+                Self::Magnifier {
+                    circular: l_circular,
+                    ..
+                },
+                Self::Magnifier {
+                    circular: r_circular,
+                    ..
+                },
+            ) => l_circular == r_circular,
+            (Self::Counter { .. }, Self::Counter { .. }) => true,
             _ => false,
         }
     }
 }
 
 impl Tool {
-    pub const ALL: [Tool; 9] = [
+    pub const ALL: [Tool; 17] = [
         Self::Rectangle {
             top_left: Point::ORIGIN,
             bottom_right: Point::ORIGIN,
@@ -145,12 +398,12 @@ impl Tool {
         Self::Line {
             start: Point::ORIGIN,
             end: Point::ORIGIN,
+            control: Point::ORIGIN,
         },
         Self::Arrow {
             start: Point::ORIGIN,
             end: Point::ORIGIN,
-            right: Point::ORIGIN,
-            left: Point::ORIGIN,
+            control: Point::ORIGIN,
         },
         Self::Rectangle {
             top_left: Point::ORIGIN,
@@ -159,10 +412,50 @@ impl Tool {
             filled: true,
             opaque: false,
         },
+        Self::Pixelate {
+            top_left: Point::ORIGIN,
+            bottom_right: Point::ORIGIN,
+            size: Size::ZERO,
+        },
+        Self::Blur {
+            top_left: Point::ORIGIN,
+            bottom_right: Point::ORIGIN,
+            size: Size::ZERO,
+        },
+        Self::Spotlight {
+            top_left: Point::ORIGIN,
+            bottom_right: Point::ORIGIN,
+            size: Size::ZERO,
+            elliptical: false,
+        },
+        Self::Spotlight {
+            top_left: Point::ORIGIN,
+            bottom_right: Point::ORIGIN,
+            size: Size::ZERO,
+            elliptical: true,
+        },
         Self::Text {
             anchor_point: Point::ORIGIN,
             text: String::new(),
         },
+        Self::Callout {
+            target: Point::ORIGIN,
+            anchor_point: Point::ORIGIN,
+            text: String::new(),
+        },
+        Self::Counter {
+            center: Point::ORIGIN,
+        },
+        Self::Magnifier {
+            source: Point::ORIGIN,
+            center: Point::ORIGIN,
+            circular: true,
+        },
+        Self::Magnifier {
+            source: Point::ORIGIN,
+            center: Point::ORIGIN,
+            circular: false,
+        },
     ];
 
     pub fn icon(&self) -> String {
@@ -187,7 +480,21 @@ impl Tool {
             Tool::FreeHand { .. } => FREE_HAND_ICON,
             Tool::Line { .. } => LINE_ICON,
             Tool::Arrow { .. } => ARROW_ICON,
+            Tool::Pixelate { .. } => PIXELATE_ICON,
+            Tool::Blur { .. } => BLUR_ICON,
+            Tool::Spotlight {
+                elliptical: false, ..
+            } => SPOTLIGHT_ICON,
+            Tool::Spotlight {
+                elliptical: true, ..
+            } => ROUND_SPOTLIGHT_ICON,
             Tool::Text { .. } => TEXT_ICON,
+            Tool::Callout { .. } => CALLOUT_ICON,
+            Tool::Counter { .. } => COUNTER_ICON,
+            Tool::Magnifier { circular: true, .. } => MAGNIFIER_ICON,
+            Tool::Magnifier {
+                circular: false, ..
+            } => SQUARE_MAGNIFIER_ICON,
             _ => ' ',
         }
         .to_string()
@@ -195,11 +502,16 @@ impl Tool {
 
     pub fn xml_tag(&self) -> String {
         match self {
-            Tool::Rectangle { .. } => "rect",
+            Tool::Rectangle { .. }
+            | Tool::Pixelate { .. }
+            | Tool::Blur { .. }
+            | Tool::Spotlight { .. } => "rect",
             Tool::Ellipse { .. } => "ellipse",
             Tool::FreeHand { .. } => "polyline",
-            Tool::Line { .. } | Tool::Arrow { .. } => "line",
+            Tool::Line { .. } | Tool::Arrow { .. } => "path",
             Tool::Text { .. } => "text",
+            Tool::Callout { .. } | Tool::Magnifier { .. } => "g",
+            Tool::Counter { .. } => "circle",
         }
         .to_string()
     }
@@ -216,6 +528,21 @@ impl Tool {
                 top_left,
                 bottom_right,
                 ..
+            }
+            | Self::Pixelate {
+                top_left,
+                bottom_right,
+                ..
+            }
+            | Self::Blur {
+                top_left,
+                bottom_right,
+                ..
+            }
+            | Self::Spotlight {
+                top_left,
+                bottom_right,
+                ..
             } => {
                 *top_left = point;
                 *bottom_right = point;
@@ -226,13 +553,19 @@ impl Tool {
             Self::FreeHand { points } => {
                 points.push(point);
             }
-            Self::Line { start, end, .. } => {
-                *start = point;
-                *end = point;
+            Self::Line {
+                start,
+                end,
+                control,
             }
-            Self::Arrow { start, end, .. } => {
+            | Self::Arrow {
+                start,
+                end,
+                control,
+            } => {
                 *start = point;
                 *end = point;
+                *control = point;
             }
             Self::Text {
                 anchor_point: anchor,
@@ -240,6 +573,21 @@ impl Tool {
             } => {
                 *anchor = point;
             }
+            Self::Callout {
+                target,
+                anchor_point,
+                ..
+            } => {
+                *target = point;
+                *anchor_point = point;
+            }
+            Self::Counter { center } => {
+                *center = point;
+            }
+            Self::Magnifier { source, center, .. } => {
+                *source = point;
+                *center = point;
+            }
         }
     }
 
@@ -250,6 +598,22 @@ impl Tool {
                 bottom_right,
                 size,
                 ..
+            }
+            | Self::Pixelate {
+                top_left,
+                bottom_right,
+                size,
+            }
+            | Self::Blur {
+                top_left,
+                bottom_right,
+                size,
+            }
+            | Self::Spotlight {
+                top_left,
+                bottom_right,
+                size,
+                ..
             } => {
                 *top_left = Point::new(initial_pt.x.min(final_pt.x), initial_pt.y.min(final_pt.y));
                 *bottom_right =
@@ -269,29 +633,28 @@ impl Tool {
             Self::FreeHand { points } => {
                 points.push(final_pt);
             }
-            Self::Line { end, .. } => {
-                *end = final_pt;
+            Self::Line {
+                start,
+                end,
+                control,
             }
-            Self::Arrow {
+            | Self::Arrow {
                 start,
                 end,
-                right,
-                left,
+                control,
             } => {
+                *start = initial_pt;
                 *end = final_pt;
-                let line = final_pt - *start;
-                let length = final_pt.distance(*start);
-                let size = if length < 60.0 { length / 2.0 } else { 30.0 };
-                let rad = line.y.atan2(line.x);
-
-                *right = Point::new(
-                    final_pt.x - size * (rad - std::f32::consts::PI / 5.0).cos(),
-                    final_pt.y - size * (rad - std::f32::consts::PI / 5.0).sin(),
-                );
-                *left = Point::new(
-                    final_pt.x - size * (rad + std::f32::consts::PI / 5.0).cos(),
-                    final_pt.y - size * (rad + std::f32::consts::PI / 5.0).sin(),
-                );
+                *control = bend(*start, *end);
+            }
+            Self::Callout { anchor_point, .. } => {
+                *anchor_point = final_pt;
+            }
+            Self::Counter { center } => {
+                *center = final_pt;
+            }
+            Self::Magnifier { center, .. } => {
+                *center = final_pt;
             }
             _ => {}
         }
@@ -304,6 +667,22 @@ impl Tool {
                 bottom_right,
                 size,
                 ..
+            }
+            | Tool::Pixelate {
+                top_left,
+                bottom_right,
+                size,
+            }
+            | Tool::Blur {
+                top_left,
+                bottom_right,
+                size,
+            }
+            | Tool::Spotlight {
+                top_left,
+                bottom_right,
+                size,
+                ..
             } => {
                 *top_left = Point::new(top_left.x * scale_factor, top_left.y * scale_factor);
                 *bottom_right =
@@ -320,21 +699,19 @@ impl Tool {
                     .map(|point| Point::new(point.x * scale_factor, point.y * scale_factor))
                     .collect();
             }
-            Tool::Line { start, end, .. } => {
-                *start = Point::new(start.x * scale_factor, start.y * scale_factor);
-                *end = Point::new(end.x * scale_factor, end.y * scale_factor);
+            Tool::Line {
+                start,
+                end,
+                control,
             }
-            Tool::Arrow {
+            | Tool::Arrow {
                 start,
                 end,
-                right,
-                left,
-                ..
+                control,
             } => {
                 *start = Point::new(start.x * scale_factor, start.y * scale_factor);
                 *end = Point::new(end.x * scale_factor, end.y * scale_factor);
-                *right = Point::new(right.x * scale_factor, right.y * scale_factor);
-                *left = Point::new(left.x * scale_factor, left.y * scale_factor);
+                *control = Point::new(control.x * scale_factor, control.y * scale_factor);
             }
             Tool::Text {
                 anchor_point: mid_point,
@@ -342,23 +719,181 @@ impl Tool {
             } => {
                 *mid_point = Point::new(mid_point.x * scale_factor, mid_point.y * scale_factor);
             }
+            Tool::Callout {
+                target,
+                anchor_point,
+                ..
+            } => {
+                *target = Point::new(target.x * scale_factor, target.y * scale_factor);
+                *anchor_point =
+                    Point::new(anchor_point.x * scale_factor, anchor_point.y * scale_factor);
+            }
+            Tool::Counter { center } => {
+                *center = Point::new(center.x * scale_factor, center.y * scale_factor);
+            }
+            Tool::Magnifier { source, center, .. } => {
+                *source = Point::new(source.x * scale_factor, source.y * scale_factor);
+                *center = Point::new(center.x * scale_factor, center.y * scale_factor);
+            }
         };
     }
 
+    /// Points defining the tool, used for its bounds.
+    fn points(&self) -> Vec<Point> {
+        match self {
+            Self::Rectangle {
+                top_left,
+                bottom_right,
+                ..
+            }
+            | Self::Pixelate {
+                top_left,
+                bottom_right,
+                ..
+            }
+            | Self::Blur {
+                top_left,
+                bottom_right,
+                ..
+            }
+            | Self::Spotlight {
+                top_left,
+                bottom_right,
+                ..
+            } => vec![*top_left, *bottom_right],
+            Self::Ellipse { center, radii, .. } => vec![*center - *radii, *center + *radii],
+            Self::FreeHand { points } => points.clone(),
+            Self::Line { start, end, .. } | Self::Arrow { start, end, .. } => vec![*start, *end],
+            Self::Text { anchor_point, .. } => vec![*anchor_point],
+            Self::Callout {
+                target,
+                anchor_point,
+                ..
+            } => vec![*target, *anchor_point],
+            Self::Counter { center } => vec![*center],
+            Self::Magnifier { source, center, .. } => vec![*source, *center],
+        }
+    }
+
+    /// Moves every point of the tool through `transform`.
+    pub fn transform(&mut self, transform: impl Fn(Point) -> Point) {
+        match self {
+            Self::Rectangle {
+                top_left,
+                bottom_right,
+                size,
+                ..
+            }
+            | Self::Pixelate {
+                top_left,
+                bottom_right,
+                size,
+            }
+            | Self::Blur {
+                top_left,
+                bottom_right,
+                size,
+            }
+            | Self::Spotlight {
+                top_left,
+                bottom_right,
+                size,
+                ..
+            } => {
+                let (start, end) = (transform(*top_left), transform(*bottom_right));
+
+                *top_left = Point::new(start.x.min(end.x), start.y.min(end.y));
+                *bottom_right = Point::new(start.x.max(end.x), start.y.max(end.y));
+                *size = Size::new(bottom_right.x - top_left.x, bottom_right.y - top_left.y);
+            }
+            Self::Ellipse { center, radii, .. } => {
+                let (start, end) = (transform(*center - *radii), transform(*center + *radii));
+
+                *center = Point::new((start.x + end.x) / 2.0, (start.y + end.y) / 2.0);
+                *radii = Vector::new((end.x - start.x) / 2.0, (end.y - start.y) / 2.0);
+            }
+            Self::FreeHand { points } => {
+                points
+                    .iter_mut()
+                    .for_each(|point| *point = transform(*point));
+            }
+            Self::Line {
+                start,
+                end,
+                control,
+            }
+            | Self::Arrow {
+                start,
+                end,
+                control,
+            } => {
+                *start = transform(*start);
+                *end = transform(*end);
+                *control = transform(*control);
+            }
+            Self::Text { anchor_point, .. } => {
+                *anchor_point = transform(*anchor_point);
+            }
+            Self::Callout {
+                target,
+                anchor_point,
+                ..
+            } => {
+                *target = transform(*target);
+                *anchor_point = transform(*anchor_point);
+            }
+            Self::Counter { center } => {
+                *center = transform(*center);
+            }
+            Self::Magnifier { source, center, .. } => {
+                *source = transform(*source);
+                *center = transform(*center);
+            }
+        }
+    }
+
+    /// Moves the control point curved lines and arrows bend toward.
+    pub fn set_control(&mut self, point: Point) {
+        if let Self::Line { control, .. } | Self::Arrow { control, .. } = self {
+            *control = point;
+        }
+    }
+
+    /// Bends curved lines and arrows evenly, as when they were drawn.
+    pub fn reset_control(&mut self) {
+        if let Self::Line {
+            start,
+            end,
+            control,
+        }
+        | Self::Arrow {
+            start,
+            end,
+            control,
+        } = self
+        {
+            *control = bend(*start, *end);
+        }
+    }
+
     pub fn update_text(&mut self, text: String) {
-        if let Self::Text { text: old_text, .. } = self {
+        if let Self::Text { text: old_text, .. } | Self::Callout { text: old_text, .. } = self {
             *old_text = text;
         }
     }
 
     pub fn is_valid(&self) -> bool {
         match self {
-            Self::Rectangle { size, .. } => size != &Size::ZERO,
+            Self::Rectangle { size, .. }
+            | Self::Pixelate { size, .. }
+            | Self::Blur { size, .. }
+            | Self::Spotlight { size, .. } => size != &Size::ZERO,
             Self::Ellipse { radii, .. } => radii != &Vector::ZERO,
             Self::FreeHand { points } => points.len() > 1,
-            Self::Line { start, end } => start != end,
-            Self::Arrow { start, end, .. } => start != end,
-            Self::Text { text, .. } => !text.is_empty(),
+            Self::Line { start, end, .. } | Self::Arrow { start, end, .. } => start != end,
+            Self::Text { text, .. } | Self::Callout { text, .. } => !text.is_empty(),
+            Self::Counter { center } => center != &Point::ORIGIN,
+            Self::Magnifier { source, center, .. } => source != center,
         }
     }
 
@@ -370,64 +905,165 @@ impl Tool {
             | Self::Ellipse {
                 filled: is_filled, ..
             } => !*is_filled,
-            Self::Line { .. } | Self::FreeHand { .. } | Self::Text { .. } => true,
+            Self::Line { .. }
+            | Self::FreeHand { .. }
+            | Self::Pixelate { .. }
+            | Self::Blur { .. }
+            | Self::Spotlight { .. }
+            | Self::Text { .. }
+            | Self::Callout { .. }
+            | Self::Counter { .. }
+            | Self::Magnifier { .. } => true,
             _ => false,
         }
     }
 
+    /// Whether dragging the corners of its bounds resizes the element.
+    ///
+    /// Text sits at a single point, its size follows the text style instead.
+    pub fn is_resizable(&self) -> bool {
+        !matches!(self, Self::Text { .. })
+    }
+
+    /// Effect and area of redaction tools, which alter the screenshot below them.
+    pub fn redaction(&self) -> Option<(Effect, Rectangle)> {
+        match self {
+            Self::Pixelate { top_left, size, .. } => {
+                Some((Effect::Pixelate, Rectangle::new(*top_left, *size)))
+            }
+            Self::Blur { top_left, size, .. } => {
+                Some((Effect::Blur, Rectangle::new(*top_left, *size)))
+            }
+            _ => None,
+        }
+    }
+
+    /// Lit area of spotlights and whether it is elliptical.
+    pub fn spotlight(&self) -> Option<(Rectangle, bool)> {
+        match self {
+            Self::Spotlight {
+                top_left,
+                size,
+                elliptical,
+                ..
+            } => Some((Rectangle::new(*top_left, *size), *elliptical)),
+            _ => None,
+        }
+    }
+
     pub fn is_text_tool(&self) -> bool {
-        matches!(self, Self::Text { .. })
+        matches!(self, Self::Text { .. } | Self::Callout { .. })
     }
 }
 
-#[derive(Debug, Default, Clone, Copy, PartialEq)]
-pub enum ToolColor {
-    #[default]
-    Red,
-    Green,
-    Blue,
-    Yellow,
-    Black,
-    White,
+/// RGBA color of an element, stored as a hex string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ToolColor {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Default for ToolColor {
+    fn default() -> Self {
+        Self::RED
+    }
 }
 
 impl From<ToolColor> for iced::Color {
     fn from(value: ToolColor) -> Self {
-        match value {
-            ToolColor::Red => iced::Color::from_rgb8(255, 0, 0),
-            ToolColor::Green => iced::Color::from_rgb8(0, 255, 0),
-            ToolColor::Blue => iced::Color::from_rgb8(0, 0, 255),
-            ToolColor::Yellow => iced::Color::from_rgb8(255, 255, 0),
-            ToolColor::Black => iced::Color::from_rgb8(0, 0, 0),
-            ToolColor::White => iced::Color::from_rgb8(255, 255, 255),
-        }
+        iced::Color::from_rgba8(value.r, value.g, value.b, value.a as f32 / 255.0)
+    }
+}
+
+impl From<ToolColor> for String {
+    fn from(value: ToolColor) -> Self {
+        value.as_hex()
+    }
+}
+
+impl TryFrom<String> for ToolColor {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        // Projects from before custom colors store the color names
+        let color = match value.as_str() {
+            "Red" => Some(Self::RED),
+            "Green" => Some(Self::GREEN),
+            "Blue" => Some(Self::BLUE),
+            "Yellow" => Some(Self::YELLOW),
+            "Black" => Some(Self::BLACK),
+            "White" => Some(Self::WHITE),
+            hex => Self::from_hex(hex),
+        };
+
+        color.ok_or_else(|| format!("Invalid color: {value}"))
     }
 }
 
 impl ToolColor {
-    pub const ALL: [ToolColor; 6] = [
-        Self::Red,
-        Self::Green,
-        Self::Blue,
-        Self::Yellow,
-        Self::Black,
-        Self::White,
+    pub const BLACK: ToolColor = ToolColor::rgb(0, 0, 0);
+    pub const BLUE: ToolColor = ToolColor::rgb(0, 0, 255);
+    pub const DEFAULT_PALETTE: [ToolColor; 6] = [
+        Self::RED,
+        Self::GREEN,
+        Self::BLUE,
+        Self::YELLOW,
+        Self::BLACK,
+        Self::WHITE,
     ];
+    pub const GREEN: ToolColor = ToolColor::rgb(0, 255, 0);
+    pub const RED: ToolColor = ToolColor::rgb(255, 0, 0);
+    pub const WHITE: ToolColor = ToolColor::rgb(255, 255, 255);
+    pub const YELLOW: ToolColor = ToolColor::rgb(255, 255, 0);
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b, a: 255 }
+    }
+
+    /// Parses `#RRGGBB` or `#RRGGBBAA`, the `#` being optional.
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.trim().trim_start_matches('#');
+
+        if !matches!(hex.len(), 6 | 8) || !hex.is_ascii() {
+            return None;
+        }
+
+        let channel = |index: usize| u8::from_str_radix(hex.get(index..index + 2)?, 16).ok();
+
+        Some(Self {
+            r: channel(0)?,
+            g: channel(2)?,
+            b: channel(4)?,
+            a: match hex.len() {
+                8 => channel(6)?,
+                _ => 255,
+            },
+        })
+    }
 
     pub fn icon(&self) -> String {
         FILLED_RECTANGLE_ICON.to_string()
     }
 
+    /// Hex notation, with the alpha channel only if the color is translucent.
     pub fn as_hex(&self) -> String {
-        match self {
-            ToolColor::Red => "#FF0000",
-            ToolColor::Green => "#00FF00",
-            ToolColor::Blue => "#0000FF",
-            ToolColor::Yellow => "#FFFF00",
-            ToolColor::Black => "#000000",
-            ToolColor::White => "#FFFFFF",
+        match self.a {
+            255 => format!("#{:02X}{:02X}{:02X}", self.r, self.g, self.b),
+            a => format!("#{:02X}{:02X}{:02X}{:02X}", self.r, self.g, self.b, a),
+        }
+    }
+
+    /// Black or white, whichever is more readable on this color.
+    pub fn contrasting(self) -> ToolColor {
+        let color = iced::Color::from(self);
+
+        match 0.299 * color.r + 0.587 * color.g + 0.114 * color.b > 0.6 {
+            true => ToolColor::BLACK,
+            false => ToolColor::WHITE,
         }
-        .to_string()
     }
 
     pub fn into_translucent_color(self) -> iced::Color {
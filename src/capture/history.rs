@@ -0,0 +1,274 @@
+use std::collections::VecDeque;
+
+use iced::{
+    Point,
+    Rectangle,
+};
+
+use crate::capture::{
+    Capture,
+    crop::CropState,
+    draw::{
+        DrawElement,
+        DrawElements,
+        DrawState,
+        ToolColor,
+    },
+    line::LineStyle,
+    mode::Mode,
+    text::TextStyle,
+};
+
+/// Maximum number of commands kept, older ones are dropped first.
+const HISTORY_LIMIT: usize = 200;
+
+/// A reversible change to the capture.
+#[derive(Debug, Clone)]
+pub enum Command {
+    Add {
+        index: usize,
+        element: DrawElement,
+    },
+    Remove {
+        index: usize,
+        element: DrawElement,
+    },
+    Clear {
+        elements: Vec<DrawElement>,
+    },
+    /// Geometry of an element changed, by moving or resizing it.
+    Edit {
+        index: usize,
+        before: DrawElement,
+        after: DrawElement,
+    },
+    Restyle {
+        index: usize,
+        before: Style,
+        after: Style,
+    },
+    /// Tool, size or color used for new elements changed.
+    Template {
+        before: DrawElement,
+        after: DrawElement,
+    },
+    /// Area selection changed, `None` meaning no area is selected.
+    Crop {
+        before: Option<Rectangle>,
+        after: Option<Rectangle>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Style {
+    pub size: u32,
+    pub color: ToolColor,
+    pub text_style: TextStyle,
+    pub line_style: LineStyle,
+}
+
+impl Style {
+    pub fn of(element: &DrawElement) -> Self {
+        Self {
+            size: element.size,
+            color: element.color,
+            text_style: element.text_style.clone(),
+            line_style: element.line_style,
+        }
+    }
+}
+
+impl Command {
+    /// Merges style changes made by the same drag, so dragging a slider or the color field is a
+    /// single step.
+    fn merge(&mut self, next: &Command) -> bool {
+        match (self, next) {
+            (
+                Command::Restyle { index, after, .. },
+                Command::Restyle {
+                    index: next_index,
+                    after: next_after,
+                    ..
+                },
+            ) if index == next_index => {
+                *after = next_after.clone();
+                true
+            }
+            (
+                Command::Template { before, after },
+                Command::Template {
+                    before: next_before,
+                    after: next_after,
+                },
+            ) if before.tool == after.tool
+                && after.tool == next_before.tool
+                && next_before.tool == next_after.tool =>
+            {
+                *after = next_after.clone();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Progress of a drag on a slider or the color field.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum Drag {
+    #[default]
+    None,
+    Started,
+    /// The drag recorded a change, which the following ones merge into.
+    Recorded,
+}
+
+#[derive(Debug, Default)]
+pub struct History {
+    undo: VecDeque<Command>,
+    redo: Vec<Command>,
+    drag: Drag,
+}
+
+impl History {
+    /// Adds the element and records it.
+    pub fn push_element(&mut self, elements: &mut DrawElements, element: DrawElement) {
+        self.push(Command::Add {
+            index: elements.len(),
+            element: element.clone(),
+        });
+        elements.push(element);
+    }
+
+    pub fn push(&mut self, command: Command) {
+        self.redo.clear();
+
+        if self.drag == Drag::Recorded
+            && let Some(last) = self.undo.back_mut()
+            && last.merge(&command)
+        {
+            return;
+        }
+
+        if self.drag == Drag::Started {
+            self.drag = Drag::Recorded;
+        }
+
+        self.undo.push_back(command);
+
+        if self.undo.len() > HISTORY_LIMIT {
+            self.undo.pop_front();
+        }
+    }
+
+    /// Starts a drag, unless one is already going.
+    pub fn begin_drag(&mut self) {
+        if self.drag == Drag::None {
+            self.drag = Drag::Started;
+        }
+    }
+
+    pub fn end_drag(&mut self) {
+        self.drag = Drag::None;
+    }
+}
+
+impl Capture {
+    pub(super) fn record(&mut self, command: Command) {
+        self.history.push(command);
+    }
+
+    pub(super) fn undo(&mut self) {
+        if let Some(command) = self.history.undo.pop_back() {
+            self.apply(&command, true);
+            self.history.redo.push(command);
+        }
+    }
+
+    pub(super) fn redo(&mut self) {
+        if let Some(command) = self.history.redo.pop() {
+            self.apply(&command, false);
+            self.history.undo.push_back(command);
+        }
+    }
+
+    fn apply(&mut self, command: &Command, reverse: bool) {
+        // Indices may shift, so the selection is dropped
+        if let Mode::Select { selected, .. } = &mut self.mode {
+            *selected = None;
+        }
+
+        match (command, reverse) {
+            (Command::Add { index, .. }, true) | (Command::Remove { index, .. }, false) => {
+                self.elements.remove(*index);
+            }
+            (Command::Add { index, element }, false)
+            | (Command::Remove { index, element }, true) => {
+                self.elements.insert(*index, element.clone());
+            }
+            (Command::Clear { elements }, true) => {
+                self.elements = elements.clone();
+            }
+            (Command::Clear { .. }, false) => {
+                self.elements.clear();
+            }
+            (
+                Command::Edit {
+                    index,
+                    before,
+                    after,
+                },
+                reverse,
+            ) => {
+                self.elements[*index] = if reverse { before } else { after }.clone();
+            }
+            (
+                Command::Restyle {
+                    index,
+                    before,
+                    after,
+                },
+                reverse,
+            ) => {
+                let style = if reverse { before } else { after };
+                let element = &mut self.elements[*index];
+                element.size = style.size;
+                element.color = style.color;
+                element.text_style = style.text_style.clone();
+                element.line_style = style.line_style;
+            }
+            (Command::Template { before, after }, reverse) => {
+                self.mode = Mode::Draw {
+                    element: if reverse { before } else { after }.clone(),
+                    state: DrawState::Idle,
+                };
+            }
+            (Command::Crop { before, after }, reverse) => {
+                self.mode = Mode::default();
+                match if reverse { before } else { after } {
+                    Some(area) => {
+                        if let Mode::Crop {
+                            top_left,
+                            bottom_right,
+                            size,
+                            state,
+                        } = &mut self.mode
+                        {
+                            *top_left = area.position();
+                            *bottom_right = Point::new(area.x + area.width, area.y + area.height);
+                            *size = area.size();
+                            *state = CropState::Area;
+                        }
+                    }
+                    None => self.mode.get_window_below_cursor(
+                        &self.windows,
+                        &self.cursor_position,
+                        self.scale_factor,
+                        self.screenshot.dimensions(),
+                    ),
+                }
+            }
+        }
+
+        self.cache.clear();
+    }
+}
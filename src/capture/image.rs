@@ -1,5 +1,6 @@
 use std::{
     fs,
+    io::Cursor,
     path::PathBuf,
 };
 
@@ -9,9 +10,13 @@ use anyhow::{
     Result,
 };
 use arboard::Clipboard;
-use chrono::Local;
+use base64::prelude::{
+    BASE64_STANDARD,
+    Engine as _,
+};
 use edit_xml::{
     Document,
+    Element,
     ElementBuilder,
 };
 use iced::Point;
@@ -20,6 +25,7 @@ use resvg::{
     usvg,
 };
 use xcap::image::{
+    GenericImage,
     ImageFormat,
     RgbaImage,
     imageops::{
@@ -33,121 +39,347 @@ use crate::{
         Capture,
         crop::CropState,
         draw::{
+            COUNTER_RADIUS_FACTOR,
             DrawElement,
             FONT_SIZE_FACTOR,
             STROKE_WIDHT_FACTOR,
             Tool,
+            ToolColor,
+        },
+        line::LineShape,
+        magnify::{
+            BORDER_WIDTH,
+            Lens,
+            magnified,
         },
         mode::Mode,
+        project::{
+            CropRect,
+            PROJECT_EXTENSION,
+            Project,
+        },
+        redact::redacted,
+        spotlight::shade,
+        text::{
+            FONT_DATABASE,
+            TextBackground,
+            TextLayout,
+            TextStyle,
+        },
     },
     config::Config,
-    consts::{
-        APPNAME,
-        FONT_NAME,
-        MEDIUM_FONT_TTF,
+    consts::FONT_NAME,
+    file_name::{
+        CaptureInfo,
+        CaptureKind,
+        image_path,
     },
-    organize_type::OrgranizeMode,
+    headless::Region,
+    output_format::OutputFormat,
 };
 
 impl Capture {
-    pub fn finalize(mut self, config: &Config) -> Result<PathBuf> {
-        if let Mode::Crop {
+    /// Monitor and area being captured, so the capture can be repeated.
+    pub fn region(&self) -> Option<Region> {
+        let Mode::Crop {
+            top_left,
+            bottom_right,
+            state: CropState::FullScreen | CropState::Window(_) | CropState::Area,
+            ..
+        } = self.mode
+        else {
+            return None;
+        };
+
+        // Windows may reach past the edges of the monitor
+        let physical =
+            |point: Point| Point::new(point.x * self.scale_factor, point.y * self.scale_factor);
+        let (top_left, bottom_right) = pixel_bounds(
+            physical(top_left),
+            physical(bottom_right),
+            self.screenshot.dimensions(),
+        );
+
+        Region::new(self.monitor.as_ref()?, top_left, bottom_right)
+    }
+
+    pub fn finalize(self, config: &Config) -> Result<PathBuf> {
+        let mut info = CaptureInfo::new(CaptureKind::Fullscreen);
+        info.monitor_name = self.monitor_name.clone();
+
+        let Mode::Crop {
             top_left,
             bottom_right,
             state,
             ..
         } = self.mode
-        {
-            let top_left = Point::new(
-                top_left.x * self.scale_factor,
-                top_left.y * self.scale_factor,
-            );
-            let bottom_right = Point::new(
-                bottom_right.x * self.scale_factor,
-                bottom_right.y * self.scale_factor,
+        else {
+            return save_image(
+                self.screenshot,
+                config,
+                None,
+                Some(self.output_format),
+                &info,
+                true,
             );
+        };
 
-            let (img_width, img_height) = self.screenshot.dimensions();
-            let annotation_overlay =
-                create_annotation_overlay(img_width, img_height, self.elements, self.scale_factor)
-                    .unwrap_or(RgbaImage::new(0, 0));
+        let project = config.save_project.then(|| {
+            let mut screenshot = self.screenshot.clone();
 
-            match state {
-                CropState::FullScreen => {
-                    overlay(&mut self.screenshot, &annotation_overlay, 0, 0);
-                }
-                CropState::Window(window) => {
-                    let mut base_image = RgbaImage::new(img_width, img_height);
-
-                    overlay(
-                        &mut base_image,
-                        &window.screenshot,
-                        top_left.x as i64,
-                        top_left.y as i64,
-                    );
+            if let CropState::Window(window) = &state {
+                overlay(
+                    &mut screenshot,
+                    &window.screenshot,
+                    (top_left.x * self.scale_factor) as i64,
+                    (top_left.y * self.scale_factor) as i64,
+                );
+            }
+
+            Project::new(
+                screenshot,
+                CropRect {
+                    top_left,
+                    bottom_right,
+                },
+                self.elements.clone(),
+                self.scale_factor,
+            )
+        });
+
+        let top_left = Point::new(
+            top_left.x * self.scale_factor,
+            top_left.y * self.scale_factor,
+        );
+        let bottom_right = Point::new(
+            bottom_right.x * self.scale_factor,
+            bottom_right.y * self.scale_factor,
+        );
+
+        let (img_width, img_height) = self.screenshot.dimensions();
+
+        let (offset, mut image) = match state {
+            CropState::FullScreen => (Point::ORIGIN, self.screenshot),
+            CropState::Window(window) => {
+                info.kind = CaptureKind::Window;
+                info.window_title = Some(window.name.clone());
+
+                let mut base_image = RgbaImage::new(img_width, img_height);
+
+                overlay(
+                    &mut base_image,
+                    &window.screenshot,
+                    top_left.x as i64,
+                    top_left.y as i64,
+                );
 
-                    overlay(&mut base_image, &annotation_overlay, 0, 0);
+                let image = crop_imm(
+                    &base_image,
+                    top_left.x as u32,
+                    top_left.y as u32,
+                    window.width as u32,
+                    window.height as u32,
+                )
+                .to_image();
 
-                    self.screenshot = crop_imm(
-                        &base_image,
-                        top_left.x as u32,
-                        top_left.y as u32,
-                        window.width as u32,
-                        window.height as u32,
-                    )
-                    .to_image();
-                }
-                CropState::Area | CropState::InProgress { .. } => {
-                    let x = top_left.x;
-                    let y = top_left.y;
-                    let size = bottom_right - top_left;
-                    let width = size.x;
-                    let height = size.y;
-                    overlay(&mut self.screenshot, &annotation_overlay, 0, 0);
-                    self.screenshot = crop_imm(
-                        &self.screenshot,
-                        x as u32,
-                        y as u32,
-                        width as u32,
-                        height as u32,
-                    )
-                    .to_image();
-                }
-                CropState::None => {
-                    return Err(Error::msg("Screenshot Cancelled!!"));
-                }
-            };
+                (top_left, image)
+            }
+            CropState::Area | CropState::InProgress { .. } | CropState::Adjusting { .. } => {
+                info.kind = CaptureKind::Area;
+
+                let ([left, top], [right, bottom]) =
+                    pixel_bounds(top_left, bottom_right, (img_width, img_height));
+                let image =
+                    crop_imm(&self.screenshot, left, top, right - left, bottom - top).to_image();
+
+                (Point::new(left as f32, top as f32), image)
+            }
+            CropState::None => {
+                return Err(Error::msg("Screenshot Cancelled!!"));
+            }
+        };
+
+        // Redactions, lenses and spotlights alter the screenshot itself, below every annotation
+        for element in &self.elements {
+            if let Some((x, y, redacted)) = redacted(&image, element, offset, self.scale_factor) {
+                image
+                    .copy_from(&redacted, x, y)
+                    .context("Failed to redact image")?;
+            }
+            if let Some((x, y, magnified)) = magnified(&image, element, offset, self.scale_factor) {
+                overlay(&mut image, &magnified, x, y);
+            }
+        }
+
+        if let Some(shade) = shade(
+            &self.elements,
+            image.width(),
+            image.height(),
+            offset,
+            self.scale_factor,
+        ) {
+            overlay(&mut image, &shade, 0, 0);
         }
 
-        save_image(self.screenshot, config)
+        // Built before the annotations are flattened into the image
+        let svg = match config.export_svg {
+            true => Some(create_annotation_svg(
+                &image,
+                offset,
+                self.elements.clone(),
+                self.scale_factor,
+            )?),
+            false => None,
+        };
+
+        if let Some(annotation_overlay) = create_annotation_overlay(
+            image.width(),
+            image.height(),
+            offset,
+            self.elements,
+            self.scale_factor,
+        ) {
+            overlay(&mut image, &annotation_overlay, 0, 0);
+        }
+
+        let image_path = save_image(image, config, None, Some(self.output_format), &info, true)?;
+
+        if let Some(project) = project {
+            project.save(&image_path.with_extension(PROJECT_EXTENSION))?;
+        }
+
+        if let Some(svg) = svg {
+            let svg_path = image_path.with_extension("svg");
+            fs::write(&svg_path, svg)
+                .with_context(|| format!("Failed to save SVG: {}", svg_path.display()))?;
+        }
+
+        Ok(image_path)
     }
 }
 
+/// Corners of an area in physical pixels, rounded to whole pixels within the screenshot.
+///
+/// Rounding each corner keeps whole sizes, like those of crop presets, exact.
+fn pixel_bounds(
+    top_left: Point,
+    bottom_right: Point,
+    (width, height): (u32, u32),
+) -> ([u32; 2], [u32; 2]) {
+    let pixels = |point: Point| {
+        [
+            (point.x.round().max(0.0) as u32).min(width),
+            (point.y.round().max(0.0) as u32).min(height),
+        ]
+    };
+
+    (pixels(top_left), pixels(bottom_right))
+}
+
+/// Rasterizes the annotations, cropped to `width` x `height` at `offset`.
 pub fn create_annotation_overlay(
     width: u32,
     height: u32,
+    offset: Point,
     shapes: Vec<DrawElement>,
     scale_factor: f32,
 ) -> Option<RgbaImage> {
     let mut pixmap = tiny_skia::Pixmap::new(width, height)?;
     let transform = usvg::Transform::identity();
 
+    let xml = annotation_document(width, height, offset, shapes, scale_factor, None);
+
+    let options = usvg::Options {
+        fontdb: FONT_DATABASE.clone(),
+        ..Default::default()
+    };
+
+    let tree = usvg::Tree::from_str(&xml, &options).expect("SVG must be valid");
+
+    resvg::render(&tree, transform, &mut pixmap.as_mut());
+
+    RgbaImage::from_vec(width, height, pixmap.take())
+}
+
+/// Builds an editable SVG with the image embedded below the annotations.
+pub fn create_annotation_svg(
+    image: &RgbaImage,
+    offset: Point,
+    shapes: Vec<DrawElement>,
+    scale_factor: f32,
+) -> Result<String> {
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .context("Failed to encode image for SVG")?;
+
+    Ok(annotation_document(
+        image.width(),
+        image.height(),
+        offset,
+        shapes,
+        scale_factor,
+        Some(&BASE64_STANDARD.encode(png)),
+    ))
+}
+
+fn annotation_document(
+    width: u32,
+    height: u32,
+    offset: Point,
+    shapes: Vec<DrawElement>,
+    scale_factor: f32,
+    background_png: Option<&str>,
+) -> String {
     let mut xml = Document::new();
 
-    let svg = ElementBuilder::new("svg")
+    let root = ElementBuilder::new("svg")
         .attribute("xmlns", "http://www.w3.org/2000/svg")
+        .attribute("xmlns:xlink", "http://www.w3.org/1999/xlink")
         .attribute("width", width.to_string())
         .attribute("height", height.to_string())
         .push_to_root_node(&mut xml);
 
-    for mut shape in shapes.into_iter() {
+    if let Some(background_png) = background_png {
+        ElementBuilder::new("image")
+            .attribute("width", width.to_string())
+            .attribute("height", height.to_string())
+            .attribute(
+                "xlink:href",
+                format!("data:image/png;base64,{background_png}"),
+            )
+            .push_to(&mut xml, root);
+    }
+
+    let svg = ElementBuilder::new("g")
+        .attribute(
+            "transform",
+            format!("translate({},{})", -offset.x, -offset.y),
+        )
+        .push_to(&mut xml, root);
+
+    let mut number = 0;
+
+    // Redactions and spotlights are part of the embedded image
+    for mut shape in shapes
+        .into_iter()
+        .filter(|shape| shape.tool.redaction().is_none() && shape.tool.spotlight().is_none())
+    {
         let element = ElementBuilder::new(shape.tool.xml_tag());
         let color = shape.color;
         let stroke_width = ((shape.size * STROKE_WIDHT_FACTOR) as f32 * scale_factor).to_string();
+        let dash = shape
+            .line_style
+            .dash
+            .pattern((shape.size * STROKE_WIDHT_FACTOR) as f32 * scale_factor);
+        // Lenses and arrow heads have sizes of their own, which the tool does not scale
+        let lens = Lens::of(&shape).map(|lens| lens.scale(scale_factor));
+        let line = LineShape::of(&shape).map(|line| line.scale(scale_factor));
 
         shape.tool.scale(scale_factor);
 
         match shape.tool {
+            Tool::Pixelate { .. } | Tool::Blur { .. } | Tool::Spotlight { .. } => {}
             Tool::Rectangle {
                 top_left,
                 size,
@@ -206,137 +438,302 @@ pub fn create_annotation_overlay(
                     .collect::<Vec<String>>()
                     .join(" ");
 
-                element
+                dashed(element, &dash)
                     .attribute("points", points_str)
                     .attribute("fill", "none")
                     .attribute("stroke", color.as_hex())
                     .attribute("stroke-width", stroke_width)
                     .push_to(&mut xml, svg);
             }
-            Tool::Line { start, end } => {
-                element
-                    .attribute("x1", start.x.to_string())
-                    .attribute("y1", start.y.to_string())
-                    .attribute("x2", end.x.to_string())
-                    .attribute("y2", end.y.to_string())
-                    .attribute("fill", "none")
-                    .attribute("stroke", color.as_hex())
-                    .attribute("stroke-width", stroke_width)
-                    .push_to(&mut xml, svg);
-            }
-            Tool::Arrow {
-                start,
-                end,
-                right,
-                left,
-            } => {
-                ElementBuilder::new("polyline")
-                    .attribute(
-                        "points",
-                        format!(
-                            "{},{}, {},{} {},{}",
-                            left.x, left.y, end.x, end.y, right.x, right.y
-                        )
-                        .to_string(),
-                    )
+            Tool::Line { .. } | Tool::Arrow { .. } => {
+                let Some(line) = line else {
+                    continue;
+                };
+
+                let path = match line.control {
+                    Some(control) => format!(
+                        "M {},{} Q {},{} {},{}",
+                        line.start.x, line.start.y, control.x, control.y, line.end.x, line.end.y
+                    ),
+                    None => format!(
+                        "M {},{} L {},{}",
+                        line.start.x, line.start.y, line.end.x, line.end.y
+                    ),
+                };
+
+                dashed(element, &dash)
+                    .attribute("d", path)
                     .attribute("fill", "none")
                     .attribute("stroke", color.as_hex())
                     .attribute("stroke-width", stroke_width.clone())
                     .push_to(&mut xml, svg);
 
-                element
-                    .attribute("x1", start.x.to_string())
-                    .attribute("y1", start.y.to_string())
-                    .attribute("x2", end.x.to_string())
-                    .attribute("y2", end.y.to_string())
-                    .attribute("fill", "none")
+                for [right, tip, left] in line.heads {
+                    let points = format!(
+                        "{},{} {},{} {},{}",
+                        right.x, right.y, tip.x, tip.y, left.x, left.y
+                    );
+
+                    match shape.line_style.filled_head {
+                        true => ElementBuilder::new("polygon").attribute("fill", color.as_hex()),
+                        false => ElementBuilder::new("polyline").attribute("fill", "none"),
+                    }
+                    .attribute("points", points)
                     .attribute("stroke", color.as_hex())
-                    .attribute("stroke-width", stroke_width)
+                    .attribute("stroke-width", stroke_width.clone())
                     .push_to(&mut xml, svg);
+                }
+            }
+            Tool::Text { anchor_point, text } => {
+                let text_style = &shape.text_style;
+                let layout = TextLayout::new(
+                    anchor_point,
+                    &text,
+                    (shape.size * FONT_SIZE_FACTOR) as f32 * scale_factor,
+                );
+
+                let background = ElementBuilder::new("rect")
+                    .attribute("x", layout.bounds.x.to_string())
+                    .attribute("y", layout.bounds.y.to_string())
+                    .attribute("width", layout.bounds.width.to_string())
+                    .attribute("height", layout.bounds.height.to_string());
+
+                match text_style.background {
+                    TextBackground::None => {}
+                    TextBackground::Filled => {
+                        background
+                            .attribute("fill", color.contrasting().as_hex())
+                            .push_to(&mut xml, svg);
+                    }
+                    TextBackground::Outline => {
+                        background
+                            .attribute("fill", "none")
+                            .attribute("stroke", color.as_hex())
+                            .attribute("stroke-width", stroke_width)
+                            .push_to(&mut xml, svg);
+                    }
+                }
+
+                push_text(&mut xml, svg, element, layout, text_style, color);
             }
-            Tool::Text {
-                anchor_point: mid_point,
+            Tool::Callout {
+                target,
+                anchor_point,
                 text,
             } => {
-                let text_size = (shape.size * FONT_SIZE_FACTOR) as f32 * scale_factor;
+                let layout = TextLayout::new(
+                    anchor_point,
+                    &text,
+                    (shape.size * FONT_SIZE_FACTOR) as f32 * scale_factor,
+                );
+                let group = element.push_to(&mut xml, svg);
 
-                let (bottom_right_x, bottom_right_y) = (
-                    mid_point.x + (text_size / 2.0),
-                    mid_point.y + (text_size / 2.0),
+                let [base_start, tip, base_end] = layout.tail(target);
+                let points = format!(
+                    "{},{} {},{} {},{}",
+                    base_start.x, base_start.y, tip.x, tip.y, base_end.x, base_end.y
                 );
+                let tail = || ElementBuilder::new("polygon").attribute("points", points.clone());
+                let bubble = || {
+                    ElementBuilder::new("rect")
+                        .attribute("x", layout.bounds.x.to_string())
+                        .attribute("y", layout.bounds.y.to_string())
+                        .attribute("width", layout.bounds.width.to_string())
+                        .attribute("height", layout.bounds.height.to_string())
+                        .attribute("rx", layout.corner_radius().to_string())
+                };
+
+                // Outlines go first, so the fills hide them where the tail joins the box
+                for outline in [tail(), bubble()] {
+                    outline
+                        .attribute("fill", "none")
+                        .attribute("stroke", color.as_hex())
+                        .attribute("stroke-width", stroke_width.clone())
+                        .push_to(&mut xml, group);
+                }
+                for fill in [tail(), bubble()] {
+                    fill.attribute("fill", color.contrasting().as_hex())
+                        .push_to(&mut xml, group);
+                }
+
+                push_text(
+                    &mut xml,
+                    group,
+                    ElementBuilder::new("text"),
+                    layout,
+                    &shape.text_style,
+                    color,
+                );
+            }
+            Tool::Magnifier { .. } => {
+                let Some(lens) = lens else {
+                    continue;
+                };
+                let group = element
+                    .attribute("fill", "none")
+                    .attribute("stroke", color.as_hex())
+                    .attribute("stroke-width", (BORDER_WIDTH * scale_factor).to_string())
+                    .push_to(&mut xml, svg);
+
+                // The enlarged copy is part of the image, only its outlines are drawn
+                for area in [lens.source, lens.lens] {
+                    match lens.circular {
+                        true => ElementBuilder::new("circle")
+                            .attribute("cx", area.center_x().to_string())
+                            .attribute("cy", area.center_y().to_string())
+                            .attribute("r", (area.width / 2.0).to_string()),
+                        false => ElementBuilder::new("rect")
+                            .attribute("x", area.x.to_string())
+                            .attribute("y", area.y.to_string())
+                            .attribute("width", area.width.to_string())
+                            .attribute("height", area.height.to_string()),
+                    }
+                    .push_to(&mut xml, group);
+                }
+
+                if let Some((start, end)) = lens.connector() {
+                    ElementBuilder::new("line")
+                        .attribute("x1", start.x.to_string())
+                        .attribute("y1", start.y.to_string())
+                        .attribute("x2", end.x.to_string())
+                        .attribute("y2", end.y.to_string())
+                        .push_to(&mut xml, group);
+                }
+            }
+            Tool::Counter { center } => {
+                number += 1;
+
+                let radius = (shape.size * COUNTER_RADIUS_FACTOR) as f32 * scale_factor;
 
                 element
-                    .attribute("x", bottom_right_x.to_string())
-                    .attribute("y", bottom_right_y.to_string())
-                    .attribute("font-family", FONT_NAME)
-                    .attribute("font-size", text_size.to_string())
+                    .attribute("cx", center.x.to_string())
+                    .attribute("cy", center.y.to_string())
+                    .attribute("r", radius.to_string())
                     .attribute("fill", color.as_hex())
-                    .add_text(text)
+                    .push_to(&mut xml, svg);
+
+                ElementBuilder::new("text")
+                    .attribute("x", center.x.to_string())
+                    .attribute("y", center.y.to_string())
+                    .attribute("text-anchor", "middle")
+                    .attribute("dominant-baseline", "central")
+                    .attribute("font-family", FONT_NAME)
+                    .attribute("font-size", radius.to_string())
+                    .attribute("fill", color.contrasting().as_hex())
+                    .add_text(number.to_string())
                     .push_to(&mut xml, svg);
             }
         };
     }
 
-    let mut options = usvg::Options::default();
-    options
-        .fontdb_mut()
-        .load_font_data(MEDIUM_FONT_TTF.to_vec());
-
-    let tree = usvg::Tree::from_str(
-        xml.write_str_with_opts(edit_xml::WriteOptions {
-            write_decl: false,
-            ..Default::default()
-        })
-        .expect("XML must be valid")
-        .as_str(),
-        &options,
-    )
-    .expect("SVG must be valid");
-
-    resvg::render(&tree, transform, &mut pixmap.as_mut());
-
-    RgbaImage::from_vec(width, height, pixmap.take())
+    xml.write_str_with_opts(edit_xml::WriteOptions {
+        write_decl: false,
+        ..Default::default()
+    })
+    .expect("XML must be valid")
 }
 
-fn save_image(image: RgbaImage, config: &Config) -> Result<PathBuf> {
-    let timestamp = Local::now().format("%Y-%m-%d_%H-%M-%S");
+/// Sets the dash pattern of the stroke, if any.
+fn dashed(element: ElementBuilder, dash: &[f32]) -> ElementBuilder {
+    match dash.is_empty() {
+        true => element,
+        false => element.attribute(
+            "stroke-dasharray",
+            dash.iter()
+                .map(f32::to_string)
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+    }
+}
 
-    let file_name = format!("{APPNAME}_{timestamp}.png");
+/// Adds the lines of text as `tspan`s of `text_element`.
+fn push_text(
+    xml: &mut Document,
+    parent: Element,
+    text_element: ElementBuilder,
+    layout: TextLayout,
+    text_style: &TextStyle,
+    color: ToolColor,
+) {
+    let text_element = text_element
+        .attribute("xml:space", "preserve")
+        .attribute("font-family", text_style.family())
+        .attribute("font-size", layout.font_size.to_string())
+        .attribute(
+            "font-weight",
+            match text_style.bold {
+                true => "bold",
+                false => "500",
+            },
+        )
+        .attribute(
+            "font-style",
+            match text_style.italic {
+                true => "italic",
+                false => "normal",
+            },
+        )
+        .attribute("dominant-baseline", "central")
+        .attribute("fill", color.as_hex())
+        .push_to(xml, parent);
+
+    for (line, position) in layout.lines {
+        ElementBuilder::new("tspan")
+            .attribute("x", position.x.to_string())
+            .attribute("y", position.y.to_string())
+            .add_text(line)
+            .push_to(xml, text_element);
+    }
+}
 
-    let folder_path = match config.organize_mode {
-        OrgranizeMode::Flat => config.folder_path.clone(),
-        OrgranizeMode::ByYear => {
-            let year = Local::now().format("%Y");
-            config.folder_path.join(year.to_string())
-        }
-        OrgranizeMode::ByYearAndMonth => {
-            let year = Local::now().format("%Y");
-            let month = Local::now().format("%m");
-            config
-                .folder_path
-                .join(year.to_string())
-                .join(month.to_string())
-        }
+/// Saves the image to `output`, or to the screenshots folder if none is given.
+///
+/// Without an explicit `format`, it is guessed from `output` before falling back to the config.
+pub fn save_image(
+    image: RgbaImage,
+    config: &Config,
+    output: Option<PathBuf>,
+    format: Option<OutputFormat>,
+    info: &CaptureInfo,
+    copy_to_clipboard: bool,
+) -> Result<PathBuf> {
+    let format = format
+        .or_else(|| output.as_deref().and_then(OutputFormat::from_path))
+        .unwrap_or(config.output_format);
+
+    let image_path = match output {
+        Some(path) => path,
+        None => image_path(
+            &config.folder_path,
+            &config.file_name_template,
+            info,
+            image.dimensions(),
+            format.extension(),
+        ),
     };
 
-    if !folder_path.exists() {
-        fs::create_dir_all(&folder_path)
+    if let Some(folder_path) = image_path.parent()
+        && !folder_path.as_os_str().is_empty()
+        && !folder_path.exists()
+    {
+        fs::create_dir_all(folder_path)
             .with_context(|| format!("Failed to create folder: {}", folder_path.display()))?;
     }
 
-    let image_path = folder_path.join(file_name);
-
-    Clipboard::new()
-        .context("Failed to initialize clipboard")?
-        .set_image(arboard::ImageData {
-            width: image.width() as usize,
-            height: image.height() as usize,
-            bytes: std::borrow::Cow::Borrowed(image.as_raw()),
-        })
-        .context("Failed to copy image to clipboard")?;
+    if copy_to_clipboard {
+        Clipboard::new()
+            .context("Failed to initialize clipboard")?
+            .set_image(arboard::ImageData {
+                width: image.width() as usize,
+                height: image.height() as usize,
+                bytes: std::borrow::Cow::Borrowed(image.as_raw()),
+            })
+            .context("Failed to copy image to clipboard")?;
+    }
 
-    image
-        .save_with_format(&image_path, ImageFormat::Png)
+    format
+        .save(&image, &image_path, config.image_quality)
         .context("Failed to save image!!")?;
 
     Ok(image_path)
@@ -1,19 +1,42 @@
-use std::rc::Rc;
+use std::{
+    path::Path,
+    rc::Rc,
+};
 
 use anyhow::{
     Context,
     Result,
 };
-use iced::widget::image::Handle;
-use xcap::Monitor;
+use iced::{
+    Point,
+    Size,
+    widget::image::Handle,
+};
+use xcap::{
+    Monitor,
+    image::RgbaImage,
+};
 
-use crate::capture::{
-    Capture,
-    CapturedWindow,
+use crate::{
+    capture::{
+        Capture,
+        CapturedWindow,
+        crop::{
+            CropPreset,
+            CropState,
+        },
+        mode::Mode,
+        project::{
+            CropRect,
+            Project,
+        },
+    },
+    config::Config,
+    desktop::Desktop,
 };
 
 impl Capture {
-    pub fn new(monitor: Monitor) -> Result<Self> {
+    pub fn new(monitor: Monitor, config: &Config) -> Result<Self> {
         let scale_factor = monitor
             .scale_factor()
             .with_context(|| "Unable to get scale factor")?;
@@ -24,11 +47,7 @@ impl Capture {
                     .into_iter()
                     .filter_map(|window| {
                         if window.current_monitor().ok()?.id().ok()? == monitor.id().ok()?
-                            && !window.is_minimized().ok()?
-                            && window.width().ok()? != 0
-                            && window.height().ok()? != 0
-                            && !window.title().ok()?.is_empty()
-                            && !window.app_name().ok()?.is_empty()
+                            && is_capturable(&window)?
                         {
                             Some(Rc::new(CapturedWindow {
                                 name: window.title().ok()?.to_string(),
@@ -50,8 +69,141 @@ impl Capture {
             .capture_image()
             .with_context(|| "Unable to capture Monitor")?;
 
-        Ok(Capture {
+        let monitor_name = monitor.name().ok();
+
+        Ok(Self {
+            monitor: Some(monitor),
+            ..Self::from_screenshot(scale_factor, monitor_name, screenshot, windows, config)
+        })
+    }
+
+    /// Capture spanning every monitor, shown in a window scaled by `scale_factor`.
+    pub fn new_desktop(desktop: Desktop, scale_factor: f32, config: &Config) -> Self {
+        let windows = xcap::Window::all()
+            .map(|windows| {
+                windows
+                    .into_iter()
+                    .filter_map(|window| {
+                        if is_capturable(&window)? {
+                            let (x, y) = desktop.to_pixels(window.x().ok()?, window.y().ok()?);
+
+                            Some(Rc::new(CapturedWindow {
+                                name: window.title().ok()?.to_string(),
+                                x,
+                                y,
+                                width: window.width().ok()? as f32 * desktop.density,
+                                height: window.height().ok()? as f32 * desktop.density,
+                                screenshot: window.capture_image().ok()?,
+                            }))
+                        } else {
+                            None
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self::from_screenshot(scale_factor, None, desktop.screenshot, windows, config)
+    }
+
+    /// Reopens a saved project for further editing, scaled down to fit within `max_size` if needed.
+    pub fn from_project(path: &Path, max_size: Size, config: &Config) -> Result<Self> {
+        let project = Project::load(path)?;
+
+        let CropRect {
+            top_left,
+            bottom_right,
+        } = project.crop;
+
+        let (width, height) = project.screenshot.dimensions();
+        let full_screen = Point::new(
+            width as f32 / project.scale_factor,
+            height as f32 / project.scale_factor,
+        );
+        let is_full_screen = top_left == Point::ORIGIN && bottom_right == full_screen;
+
+        // Only the window shrinks, shapes keep the project's scale for export
+        let zoom = (max_size.width / full_screen.x)
+            .min(max_size.height / full_screen.y)
+            .min(1.0);
+
+        let mut capture = Self::from_screenshot(
+            project.scale_factor,
+            None,
+            project.screenshot,
+            Vec::new(),
+            config,
+        );
+
+        capture.zoom = zoom;
+        capture.elements = project.elements;
+        capture.mode = Mode::Crop {
+            top_left,
+            bottom_right,
+            size: (bottom_right - top_left).into(),
+            state: match is_full_screen {
+                true => CropState::FullScreen,
+                false => CropState::Area,
+            },
+        };
+
+        Ok(capture)
+    }
+
+    /// Opens an image file, scaled down to fit within `max_size` if needed.
+    pub fn from_image(
+        path: &Path,
+        max_size: Size,
+        scale_factor: f32,
+        config: &Config,
+    ) -> Result<Self> {
+        let image = xcap::image::open(path)
+            .with_context(|| format!("Unable to open image: {}", path.display()))?
+            .into_rgba8();
+
+        let (width, height) = image.dimensions();
+        let scale_factor = scale_factor
+            .max(width as f32 / max_size.width)
+            .max(height as f32 / max_size.height);
+
+        Ok(Self::from_screenshot(
+            scale_factor,
+            None,
+            image,
+            Vec::new(),
+            config,
+        ))
+    }
+
+    /// Size of the screenshot in logical pixels.
+    pub fn logical_size(&self) -> Size {
+        let (width, height) = self.screenshot.dimensions();
+
+        Size::new(
+            width as f32 / self.scale_factor,
+            height as f32 / self.scale_factor,
+        )
+    }
+
+    /// Size of the window showing the screenshot, in logical pixels.
+    pub fn window_size(&self) -> Size {
+        self.logical_size() * self.zoom
+    }
+
+    fn from_screenshot(
+        scale_factor: f32,
+        monitor_name: Option<String>,
+        screenshot: RgbaImage,
+        windows: Vec<Rc<CapturedWindow>>,
+        config: &Config,
+    ) -> Self {
+        let dimensions = screenshot.dimensions();
+
+        Capture {
             scale_factor,
+            zoom: 1.0,
+            monitor_name,
+            monitor: None,
             screenshot: screenshot.clone(),
             screenshot_handle: Handle::from_rgba(
                 screenshot.width(),
@@ -60,10 +212,120 @@ impl Capture {
             ),
             windows,
             toolbar_at_top: true,
+            output_format: config.output_format,
+            // Free selection always comes first, sizes larger than the screen are left out
+            crop_presets: std::iter::once(CropPreset::Free)
+                .chain(
+                    config
+                        .crop_presets
+                        .iter()
+                        .copied()
+                        .filter(|preset| *preset != CropPreset::Free && preset.fits(dimensions)),
+                )
+                .collect(),
+            crop_preset: CropPreset::Free,
+            palette: config.palette.clone(),
+            color_picker: None,
+            eyedropper: false,
+            text_content: Default::default(),
+            size_input: None,
             cursor_position: Default::default(),
+            modifiers: Default::default(),
             mode: Default::default(),
             elements: Default::default(),
+            history: Default::default(),
             cache: Default::default(),
-        })
+        }
+    }
+}
+
+fn is_capturable(window: &xcap::Window) -> Option<bool> {
+    Some(
+        !window.is_minimized().ok()?
+            && window.width().ok()? != 0
+            && window.height().ok()? != 0
+            && !window.title().ok()?.is_empty()
+            && !window.app_name().ok()?.is_empty(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use iced::Vector;
+
+    use super::*;
+    use crate::capture::{
+        PROJECT_EXTENSION,
+        draw::{
+            DrawElement,
+            Tool,
+        },
+        image::create_annotation_svg,
+    };
+
+    #[test]
+    fn reopens_projects_at_their_own_scale() {
+        let screenshot = RgbaImage::new(400, 200);
+        let elements = vec![
+            DrawElement {
+                tool: Tool::Arrow {
+                    start: Point::new(10.0, 10.0),
+                    end: Point::new(150.0, 80.0),
+                    control: Point::new(80.0, 45.0),
+                },
+                size: 3,
+                ..Default::default()
+            },
+            DrawElement {
+                tool: Tool::Ellipse {
+                    center: Point::new(100.0, 50.0),
+                    radii: Vector::new(40.0, 20.0),
+                    filled: false,
+                },
+                size: 5,
+                ..Default::default()
+            },
+            DrawElement {
+                tool: Tool::Counter {
+                    center: Point::new(20.0, 80.0),
+                },
+                size: 2,
+                ..Default::default()
+            },
+        ];
+
+        let project = Project::new(
+            screenshot.clone(),
+            CropRect {
+                top_left: Point::ORIGIN,
+                bottom_right: Point::new(200.0, 100.0),
+            },
+            elements.clone(),
+            2.0,
+        );
+
+        let path = std::env::temp_dir().join(format!(
+            "capter-reopen-{}.{PROJECT_EXTENSION}",
+            std::process::id()
+        ));
+        project.save(&path).unwrap();
+
+        // Half the window size the project was saved at
+        let capture = Capture::from_project(&path, Size::new(100.0, 50.0), &Config::default());
+        fs::remove_file(&path).unwrap();
+        let capture = capture.unwrap();
+
+        assert_eq!(capture.window_size(), Size::new(100.0, 50.0));
+
+        let export = |elements, scale_factor| {
+            create_annotation_svg(&screenshot, Point::ORIGIN, elements, scale_factor).unwrap()
+        };
+
+        assert_eq!(
+            export(capture.elements.clone(), capture.scale_factor),
+            export(elements, 2.0)
+        );
     }
 }
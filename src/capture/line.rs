@@ -0,0 +1,185 @@
+use std::{
+    f32::consts::PI,
+    fmt::Display,
+};
+
+use iced::{
+    Point,
+    Vector,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::capture::draw::{
+    DrawElement,
+    Tool,
+};
+
+/// Offset of the control point of new curves relative to their length.
+const BEND_FACTOR: f32 = 0.25;
+
+/// Angle between an arrow head side and the line.
+const HEAD_ANGLE: f32 = PI / 5.0;
+
+/// Length of the arrow head sides, shorter for short arrows.
+const HEAD_LENGTH: f32 = 30.0;
+
+/// Segments approximating a curve when hit testing.
+const CURVE_SEGMENTS: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LineStyle {
+    pub dash: Dash,
+    pub filled_head: bool,
+    pub double_head: bool,
+    /// Bends lines and arrows toward their control point.
+    pub curved: bool,
+    /// Joins the lens of a magnifier to its source.
+    pub connector: bool,
+}
+
+impl Default for LineStyle {
+    fn default() -> Self {
+        Self {
+            dash: Dash::default(),
+            filled_head: false,
+            double_head: false,
+            curved: false,
+            connector: true,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Dash {
+    #[default]
+    Solid,
+    Dashed,
+    Dotted,
+}
+
+impl Dash {
+    pub const ALL: [Dash; 3] = [Self::Solid, Self::Dashed, Self::Dotted];
+
+    /// Lengths of dashes and gaps for a stroke of `width`, empty if solid.
+    pub fn pattern(self, width: f32) -> Vec<f32> {
+        match self {
+            Dash::Solid => Vec::new(),
+            Dash::Dashed => vec![width * 3.0, width * 2.0],
+            Dash::Dotted => vec![width, width],
+        }
+    }
+}
+
+impl Display for Dash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Dash::Solid => write!(f, "Solid"),
+            Dash::Dashed => write!(f, "Dashed"),
+            Dash::Dotted => write!(f, "Dotted"),
+        }
+    }
+}
+
+/// Control point bending a new curve from `start` to `end` to its left.
+pub fn bend(start: Point, end: Point) -> Point {
+    let line = end - start;
+    let middle = start + line * 0.5;
+
+    middle + Vector::new(line.y, -line.x) * BEND_FACTOR
+}
+
+/// Geometry of a line or an arrow, in the coordinates of its element.
+pub struct LineShape {
+    pub start: Point,
+    pub end: Point,
+    /// Control point of the quadratic curve, if curved.
+    pub control: Option<Point>,
+    /// Sides of each arrow head around its tip.
+    pub heads: Vec<[Point; 3]>,
+}
+
+impl LineShape {
+    pub fn of(element: &DrawElement) -> Option<Self> {
+        let (start, end, control, arrow) = match element.tool {
+            Tool::Line {
+                start,
+                end,
+                control,
+            } => (start, end, control, false),
+            Tool::Arrow {
+                start,
+                end,
+                control,
+            } => (start, end, control, true),
+            _ => return None,
+        };
+
+        let style = element.line_style;
+        let control = style.curved.then_some(control);
+
+        let length = start.distance(end);
+        let head_length = if length < HEAD_LENGTH * 2.0 {
+            length / 2.0
+        } else {
+            HEAD_LENGTH
+        };
+
+        // Heads follow the direction the line has when reaching them
+        let mut heads = Vec::new();
+        if arrow {
+            heads.push(head(end, control.unwrap_or(start), head_length));
+            if style.double_head {
+                heads.push(head(start, control.unwrap_or(end), head_length));
+            }
+        }
+
+        Some(Self {
+            start,
+            end,
+            control,
+            heads,
+        })
+    }
+
+    pub fn scale(self, scale_factor: f32) -> Self {
+        let scale = |point: Point| Point::new(point.x * scale_factor, point.y * scale_factor);
+
+        Self {
+            start: scale(self.start),
+            end: scale(self.end),
+            control: self.control.map(scale),
+            heads: self.heads.into_iter().map(|head| head.map(scale)).collect(),
+        }
+    }
+
+    /// Points along the line, with curves flattened into segments.
+    pub fn points(&self) -> Vec<Point> {
+        match self.control {
+            Some(control) => (0..=CURVE_SEGMENTS)
+                .map(|step| {
+                    let t = step as f32 / CURVE_SEGMENTS as f32;
+                    let (a, b, c) = ((1.0 - t) * (1.0 - t), 2.0 * (1.0 - t) * t, t * t);
+
+                    Point::new(
+                        a * self.start.x + b * control.x + c * self.end.x,
+                        a * self.start.y + b * control.y + c * self.end.y,
+                    )
+                })
+                .collect(),
+            None => vec![self.start, self.end],
+        }
+    }
+}
+
+/// Head at `tip` of a line coming from `from`.
+fn head(tip: Point, from: Point, length: f32) -> [Point; 3] {
+    let line = tip - from;
+    let angle = line.y.atan2(line.x);
+    let side = |angle: f32| Point::new(tip.x - length * angle.cos(), tip.y - length * angle.sin());
+
+    [side(angle - HEAD_ANGLE), tip, side(angle + HEAD_ANGLE)]
+}
@@ -0,0 +1,153 @@
+use iced::{
+    Color,
+    Pixels,
+    Point,
+    Size,
+    Vector,
+    widget::canvas::{
+        Frame,
+        Path,
+        Stroke,
+        Text,
+    },
+};
+use xcap::image::RgbaImage;
+
+use crate::{
+    capture::draw::ToolColor,
+    consts::MEDIUM_FONT,
+};
+
+/// Pixels shown across the loupe, odd so one is centered.
+const CELLS: i64 = 17;
+
+/// Edge of each pixel in the loupe.
+const CELL_SIZE: f32 = 8.0;
+
+/// Distance between the cursor and the loupe.
+const OFFSET: f32 = 24.0;
+
+const TEXT_SIZE: f32 = 12.0;
+
+const LINE_HEIGHT: f32 = 16.0;
+
+const PADDING: f32 = 6.0;
+
+/// Draws the pixels around `cursor` enlarged, with their position and color below.
+///
+/// The cursor is in logical coordinates, the readout shows physical ones.
+pub fn draw(frame: &mut Frame, image: &RgbaImage, cursor: Point, scale_factor: f32) {
+    let (x, y) = (
+        (cursor.x * scale_factor).floor() as i64,
+        (cursor.y * scale_factor).floor() as i64,
+    );
+    let pixel = |x: i64, y: i64| {
+        let pixel = image.get_pixel_checked(u32::try_from(x).ok()?, u32::try_from(y).ok()?)?;
+        Some(ToolColor::rgb(pixel[0], pixel[1], pixel[2]))
+    };
+
+    let side = CELLS as f32 * CELL_SIZE;
+    let lines = [
+        format!("{x}, {y}"),
+        pixel(x, y).map_or_else(String::new, |color| color.as_hex()),
+        pixel(x, y).map_or_else(String::new, |color| {
+            format!("{}, {}, {}", color.r, color.g, color.b)
+        }),
+        "Press C to copy".to_string(),
+    ];
+    let size = Size::new(
+        side,
+        side + lines.len() as f32 * LINE_HEIGHT + PADDING * 2.0,
+    );
+
+    // Stays on screen, switching to the other side of the cursor near the edges
+    let position = Point::new(
+        match cursor.x + OFFSET + size.width > frame.width() {
+            true => cursor.x - OFFSET - size.width,
+            false => cursor.x + OFFSET,
+        },
+        match cursor.y + OFFSET + size.height > frame.height() {
+            true => cursor.y - OFFSET - size.height,
+            false => cursor.y + OFFSET,
+        },
+    );
+
+    frame.fill_rectangle(position, size, Color::from_rgba(0.0, 0.0, 0.0, 0.8));
+
+    let half = CELLS / 2;
+    (0..CELLS).for_each(|row| {
+        (0..CELLS).for_each(|column| {
+            if let Some(color) = pixel(x + column - half, y + row - half) {
+                frame.fill_rectangle(
+                    position + Vector::new(column as f32 * CELL_SIZE, row as f32 * CELL_SIZE),
+                    Size::new(CELL_SIZE, CELL_SIZE),
+                    Color::from(color),
+                );
+            }
+        });
+    });
+
+    let grid = Path::new(|builder| {
+        (1..CELLS).for_each(|line| {
+            let offset = line as f32 * CELL_SIZE;
+
+            builder.move_to(position + Vector::new(offset, 0.0));
+            builder.line_to(position + Vector::new(offset, side));
+            builder.move_to(position + Vector::new(0.0, offset));
+            builder.line_to(position + Vector::new(side, offset));
+        });
+    });
+    frame.stroke(
+        &grid,
+        Stroke::default()
+            .with_width(1.0)
+            .with_color(Color::from_rgba(0.0, 0.0, 0.0, 0.2)),
+    );
+
+    // Crosshair along the row and column of the pixel under the cursor
+    let center = half as f32 * CELL_SIZE;
+    let crosshair = Stroke::default()
+        .with_width(1.0)
+        .with_color(Color::from_rgba(1.0, 1.0, 1.0, 0.5));
+    frame.stroke_rectangle(
+        position + Vector::new(0.0, center),
+        Size::new(side, CELL_SIZE),
+        crosshair,
+    );
+    frame.stroke_rectangle(
+        position + Vector::new(center, 0.0),
+        Size::new(CELL_SIZE, side),
+        crosshair,
+    );
+
+    let outline = Stroke::default().with_width(1.0).with_color(Color::BLACK);
+    frame.stroke_rectangle(
+        position + Vector::new(center, center),
+        Size::new(CELL_SIZE, CELL_SIZE),
+        outline.with_color(Color::WHITE).with_width(2.0),
+    );
+    frame.stroke_rectangle(position, Size::new(side, side), outline);
+    frame.stroke_rectangle(position, size, outline.with_color(Color::WHITE));
+
+    // Swatch next to the hex value
+    if let Some(color) = pixel(x, y) {
+        let swatch = LINE_HEIGHT - 4.0;
+
+        frame.fill_rectangle(
+            position + Vector::new(side - PADDING - swatch, side + PADDING + LINE_HEIGHT),
+            Size::new(swatch, swatch),
+            Color::from(color),
+        );
+    }
+
+    lines.into_iter().enumerate().for_each(|(index, line)| {
+        frame.fill_text(Text {
+            content: line,
+            position: position + Vector::new(PADDING, side + PADDING + index as f32 * LINE_HEIGHT),
+            color: Color::WHITE,
+            size: Pixels(TEXT_SIZE),
+            font: MEDIUM_FONT,
+            ..Default::default()
+        });
+    });
+}
@@ -0,0 +1,134 @@
+use iced::{
+    Point,
+    Rectangle,
+    Size,
+    Vector,
+};
+use xcap::image::{
+    Rgba,
+    RgbaImage,
+};
+
+use crate::capture::draw::{
+    DrawElement,
+    LENS_RADIUS_FACTOR,
+    Tool,
+};
+
+/// Enlargement of the area shown in the lens.
+const ZOOM: f32 = 2.0;
+
+/// Width of the lens and source outlines, in logical pixels.
+pub const BORDER_WIDTH: f32 = 3.0;
+
+/// Areas of a magnifier, in the coordinates of its element.
+pub struct Lens {
+    /// Area that is enlarged.
+    pub source: Rectangle,
+    /// Area showing the enlarged copy.
+    pub lens: Rectangle,
+    pub circular: bool,
+    /// Whether a line joins both areas.
+    pub connected: bool,
+}
+
+impl Lens {
+    pub fn of(element: &DrawElement) -> Option<Self> {
+        let Tool::Magnifier {
+            source,
+            center,
+            circular,
+        } = element.tool
+        else {
+            return None;
+        };
+
+        let radius = (element.size * LENS_RADIUS_FACTOR) as f32;
+        let square = |center: Point, radius: f32| {
+            Rectangle::new(
+                center - Vector::new(radius, radius),
+                Size::new(radius * 2.0, radius * 2.0),
+            )
+        };
+
+        Some(Self {
+            source: square(source, radius / ZOOM),
+            lens: square(center, radius),
+            circular,
+            connected: element.line_style.connector,
+        })
+    }
+
+    pub fn scale(self, scale_factor: f32) -> Self {
+        Self {
+            source: self.source * scale_factor,
+            lens: self.lens * scale_factor,
+            circular: self.circular,
+            connected: self.connected,
+        }
+    }
+
+    /// Line joining the outlines of both areas, if connected and apart.
+    pub fn connector(&self) -> Option<(Point, Point)> {
+        let (from, to) = (self.source.center(), self.lens.center());
+        let distance = from.distance(to);
+
+        if !self.connected || distance == 0.0 {
+            return None;
+        }
+
+        let direction = (to - from) * (1.0 / distance);
+        // Distance from the center to the outline along the direction
+        let reach = |half_size: f32| match self.circular {
+            true => half_size,
+            false => half_size / direction.x.abs().max(direction.y.abs()),
+        };
+        let (start, end) = (reach(self.source.width / 2.0), reach(self.lens.width / 2.0));
+
+        (start + end < distance).then(|| (from + direction * start, to - direction * end))
+    }
+}
+
+/// Enlarged pixels of the source area, with the position of the lens in `image`.
+///
+/// The element is in logical coordinates, `offset` is the position of `image` in physical ones.
+pub fn magnified(
+    image: &RgbaImage,
+    element: &DrawElement,
+    offset: Point,
+    scale_factor: f32,
+) -> Option<(i64, i64, RgbaImage)> {
+    let lens = Lens::of(element)?;
+
+    let source = Point::new(
+        lens.source.x * scale_factor - offset.x,
+        lens.source.y * scale_factor - offset.y,
+    );
+    let side = (lens.lens.width * scale_factor).round() as u32;
+    let radius = side as f32 / 2.0;
+
+    let magnified = RgbaImage::from_fn(side, side, |x, y| {
+        let (x, y) = (x as f32 + 0.5, y as f32 + 0.5);
+
+        if lens.circular && (x - radius).powi(2) + (y - radius).powi(2) > radius * radius {
+            return Rgba([0, 0, 0, 0]);
+        }
+
+        let (source_x, source_y) = (source.x + x / ZOOM, source.y + y / ZOOM);
+
+        if source_x < 0.0 || source_y < 0.0 {
+            return Rgba([0, 0, 0, 0]);
+        }
+
+        image
+            .get_pixel_checked(source_x as u32, source_y as u32)
+            .copied()
+            .unwrap_or(Rgba([0, 0, 0, 0]))
+    });
+
+    Some((
+        (lens.lens.x * scale_factor - offset.x).round() as i64,
+        (lens.lens.y * scale_factor - offset.y).round() as i64,
+        magnified,
+    ))
+}
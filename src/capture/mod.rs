@@ -1,34 +1,64 @@
 mod canvas;
-mod image;
+pub mod image;
 mod init;
+mod project;
 mod update;
 mod view;
 
 mod crop;
 mod draw;
+mod history;
+mod line;
+mod loupe;
+mod magnify;
 mod mode;
+mod picker;
+mod redact;
+mod select;
+mod snap;
+mod spotlight;
+mod text;
 
 use std::rc::Rc;
 
-use draw::{
-    Tool,
-    ToolColor,
-};
+pub use crop::CropPreset;
+use crop::SizeInput;
+use draw::Tool;
+pub use draw::ToolColor;
+use history::History;
 use iced::{
     Point,
+    Vector,
+    keyboard::Modifiers,
     widget::{
         canvas::Cache,
         image::Handle,
+        text_editor,
     },
 };
+use line::Dash;
 use mode::Mode;
-use xcap::image::RgbaImage;
+use picker::ColorPicker;
+pub use project::PROJECT_EXTENSION;
+use text::TextBackground;
+use xcap::{
+    Monitor,
+    image::RgbaImage,
+};
 
-use crate::capture::draw::DrawElements;
+use crate::{
+    capture::draw::DrawElements,
+    output_format::OutputFormat,
+};
 
 pub struct Capture {
     // Attributes
     scale_factor: f32,
+    /// Display scale of the window, below 1 for projects shrunk to fit the screen.
+    zoom: f32,
+    monitor_name: Option<String>,
+    /// Monitor the screenshot was taken from, unknown for desktops and opened files.
+    monitor: Option<Monitor>,
 
     // Screenshot
     screenshot: RgbaImage,
@@ -37,11 +67,21 @@ pub struct Capture {
 
     // UI
     toolbar_at_top: bool,
+    output_format: OutputFormat,
+    crop_presets: Vec<CropPreset>,
+    crop_preset: CropPreset,
+    palette: Vec<ToolColor>,
+    color_picker: Option<ColorPicker>,
+    eyedropper: bool,
+    text_content: text_editor::Content,
+    size_input: Option<SizeInput>,
 
     // State
     cursor_position: Point,
+    modifiers: Modifiers,
     mode: Mode,
     elements: DrawElements,
+    history: History,
     cache: Cache,
 }
 
@@ -49,19 +89,50 @@ pub struct Capture {
 pub enum Message {
     MoveToolBar,
     Undo,
+    Redo,
     Done,
     Cancel,
     ChangeTool(Tool),
+    Select,
+    Delete,
     ChangeSize(u32),
     ChangeColor(ToolColor),
-    UpdateText(String),
+    ToggleColorPicker,
+    PickHueSaturation(f32, f32),
+    PickValue(f32),
+    PickAlpha(u8),
+    /// A slider or the color field was released.
+    EndDrag,
+    UpdateHex(String),
+    ToggleFavorite,
+    ToggleEyedropper,
+    ChangeOutputFormat(OutputFormat),
+    ChangeCropPreset(CropPreset),
+    Nudge(Vector),
+    CopyColor,
+    ChangeCropWidth(String),
+    ChangeCropHeight(String),
+    SubmitCropSize,
+    EditText(text_editor::Action),
+    CommitText,
+    ToggleBold,
+    ToggleItalic,
+    ChangeTextBackground(TextBackground),
+    ChangeFont(String),
+    ChangeDash(Dash),
+    ToggleFilledHead,
+    ToggleDoubleHead,
+    ToggleCurved,
+    ToggleConnector,
     MousePressed,
     MouseMoved(Point),
+    ModifiersChanged(Modifiers),
     MouseReleased,
 }
 
 pub enum Request {
     Close,
+    SavePalette(Vec<ToolColor>),
 }
 
 #[derive(Debug)]
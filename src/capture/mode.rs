@@ -12,6 +12,7 @@ use crate::capture::{
         DrawElement,
         DrawState,
     },
+    select::SelectState,
 };
 
 #[derive(Debug)]
@@ -26,6 +27,10 @@ pub enum Mode {
         size: Size,
         state: CropState,
     },
+    Select {
+        selected: Option<usize>,
+        state: SelectState,
+    },
 }
 
 impl Default for Mode {
@@ -40,10 +45,6 @@ impl Default for Mode {
 }
 
 impl Mode {
-    pub fn is_draw_mode(&self) -> bool {
-        matches!(self, Self::Draw { .. })
-    }
-
     pub fn get_window_below_cursor(
         &mut self,
         windows: &[Rc<CapturedWindow>],
@@ -99,7 +100,9 @@ impl Mode {
             state: status,
         } = self
         {
-            shape.tool.is_valid() || (shape.tool.is_text_tool() && status.is_waiting_for_input())
+            // Text tools are shown while placed, before any text is typed
+            shape.tool.is_valid()
+                || (shape.tool.is_text_tool() && !matches!(status, DrawState::Idle))
         } else {
             false
         }
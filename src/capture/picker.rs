@@ -0,0 +1,234 @@
+use iced::{
+    Color,
+    Event,
+    Point,
+    Rectangle,
+    Renderer,
+    Size,
+    mouse::{
+        self,
+        Cursor,
+    },
+    widget::{
+        Action,
+        canvas::{
+            Fill,
+            Frame,
+            Geometry,
+            Gradient,
+            Path,
+            Program,
+            Stroke,
+            gradient::Linear,
+        },
+    },
+};
+
+use crate::{
+    capture::{
+        Message,
+        draw::ToolColor,
+    },
+    theme::Theme,
+};
+
+/// Favorite colors kept in the palette, the oldest one is dropped beyond it.
+pub const PALETTE_LIMIT: usize = 8;
+
+/// State of the color picker popup.
+///
+/// Hue, saturation and value are kept apart from the color, so the hue survives grays.
+#[derive(Debug, Clone)]
+pub struct ColorPicker {
+    pub hue: f32,
+    pub saturation: f32,
+    pub value: f32,
+    pub alpha: u8,
+    pub hex: String,
+}
+
+impl ColorPicker {
+    pub fn new(color: ToolColor) -> Self {
+        let mut picker = Self {
+            hue: 0.0,
+            saturation: 0.0,
+            value: 0.0,
+            alpha: 255,
+            hex: String::new(),
+        };
+        picker.set_color(color);
+        picker
+    }
+
+    pub fn color(&self) -> ToolColor {
+        let [r, g, b] = hsv_to_rgb(self.hue, self.saturation, self.value);
+
+        ToolColor {
+            r,
+            g,
+            b,
+            a: self.alpha,
+        }
+    }
+
+    pub fn set_color(&mut self, color: ToolColor) {
+        let (hue, saturation, value) = rgb_to_hsv(color.r, color.g, color.b);
+
+        if saturation > 0.0 && value > 0.0 {
+            self.hue = hue;
+        }
+        if value > 0.0 {
+            self.saturation = saturation;
+        }
+        self.value = value;
+        self.alpha = color.a;
+        self.hex = color.as_hex();
+    }
+
+    /// Updates the hex input after the color changed through the other controls.
+    pub fn sync_hex(&mut self) {
+        self.hex = self.color().as_hex();
+    }
+}
+
+/// Hue in degrees, saturation and value within `0.0..=1.0`.
+fn rgb_to_hsv(r: u8, g: u8, b: u8) -> (f32, f32, f32) {
+    let (r, g, b) = (r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
+
+    let max = r.max(g).max(b);
+    let delta = max - r.min(g).min(b);
+
+    let hue = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+
+    let saturation = if max == 0.0 { 0.0 } else { delta / max };
+
+    (hue, saturation, max)
+}
+
+fn hsv_to_rgb(hue: f32, saturation: f32, value: f32) -> [u8; 3] {
+    let chroma = value * saturation;
+    let sector = hue.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+
+    let (r, g, b) = match sector as u8 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+
+    let m = value - chroma;
+
+    [r, g, b].map(|channel| ((channel + m) * 255.0).round() as u8)
+}
+
+/// Field picking the hue horizontally and the saturation vertically.
+pub struct HueSaturationField {
+    pub hue: f32,
+    pub saturation: f32,
+}
+
+impl Program<Message, Theme> for HueSaturationField {
+    /// Whether the pointer is dragging across the field.
+    type State = bool;
+
+    fn update(
+        &self,
+        dragging: &mut Self::State,
+        event: &Event,
+        bounds: Rectangle,
+        cursor: Cursor,
+    ) -> Option<Action<Message>> {
+        let pick = |position: Point| {
+            let hue = (position.x / bounds.width).clamp(0.0, 1.0) * 360.0;
+            let saturation = 1.0 - (position.y / bounds.height).clamp(0.0, 1.0);
+
+            Action::publish(Message::PickHueSaturation(hue, saturation)).and_capture()
+        };
+
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                let position = cursor.position_in(bounds)?;
+                *dragging = true;
+                Some(pick(position))
+            }
+            Event::Mouse(mouse::Event::CursorMoved { .. }) if *dragging => {
+                let position = cursor.position_from(bounds.position())?;
+                Some(pick(position))
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) if *dragging => {
+                *dragging = false;
+                Some(Action::publish(Message::EndDrag).and_capture())
+            }
+            _ => None,
+        }
+    }
+
+    fn draw(
+        &self,
+        _dragging: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: Cursor,
+    ) -> Vec<Geometry<Renderer>> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let size = bounds.size();
+
+        let hues = (0..=6).fold(
+            Linear::new(Point::ORIGIN, Point::new(size.width, 0.0)),
+            |gradient, sector| {
+                let [r, g, b] = hsv_to_rgb(sector as f32 * 60.0, 1.0, 1.0);
+                gradient.add_stop(sector as f32 / 6.0, Color::from_rgb8(r, g, b))
+            },
+        );
+        frame.fill_rectangle(Point::ORIGIN, size, Fill::from(Gradient::Linear(hues)));
+
+        let saturation = Linear::new(Point::ORIGIN, Point::new(0.0, size.height))
+            .add_stop(0.0, Color::TRANSPARENT)
+            .add_stop(1.0, Color::WHITE);
+        frame.fill_rectangle(
+            Point::ORIGIN,
+            size,
+            Fill::from(Gradient::Linear(saturation)),
+        );
+
+        let marker = Point::new(
+            self.hue / 360.0 * size.width,
+            (1.0 - self.saturation) * size.height,
+        );
+        frame.stroke(
+            &Path::circle(marker, 5.0),
+            Stroke::default().with_width(2.0).with_color(Color::WHITE),
+        );
+        frame.stroke(
+            &Path::rectangle(Point::ORIGIN, Size::new(size.width, size.height)),
+            Stroke::default().with_color(Color::from_rgba(0.0, 0.0, 0.0, 0.3)),
+        );
+
+        vec![frame.into_geometry()]
+    }
+
+    fn mouse_interaction(
+        &self,
+        dragging: &Self::State,
+        bounds: Rectangle,
+        cursor: Cursor,
+    ) -> mouse::Interaction {
+        if *dragging || cursor.is_over(bounds) {
+            mouse::Interaction::Crosshair
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use std::{
+    fs,
+    io::Cursor,
+    path::Path,
+};
+
+use anyhow::{
+    Context,
+    Result,
+    bail,
+};
+use base64::prelude::{
+    BASE64_STANDARD,
+    Engine as _,
+};
+use iced::{
+    Point,
+    Size,
+    Vector,
+};
+use serde::{
+    Deserialize,
+    Deserializer,
+    Serialize,
+    Serializer,
+};
+use xcap::image::{
+    ImageFormat,
+    RgbaImage,
+    load_from_memory_with_format,
+};
+
+use crate::capture::draw::DrawElements;
+
+pub const PROJECT_EXTENSION: &str = "capter";
+
+const PROJECT_VERSION: u32 = 1;
+
+/// Re-editable capture, stored as JSON with the screenshot embedded as PNG.
+#[derive(Serialize, Deserialize)]
+pub struct Project {
+    version: u32,
+    pub scale_factor: f32,
+    pub crop: CropRect,
+    pub elements: DrawElements,
+    #[serde(with = "png_base64")]
+    pub screenshot: RgbaImage,
+}
+
+/// Crop selection in logical coordinates.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CropRect {
+    #[serde(with = "PointDef")]
+    pub top_left: Point,
+    #[serde(with = "PointDef")]
+    pub bottom_right: Point,
+}
+
+impl Project {
+    pub fn new(
+        screenshot: RgbaImage,
+        crop: CropRect,
+        elements: DrawElements,
+        scale_factor: f32,
+    ) -> Self {
+        Self {
+            version: PROJECT_VERSION,
+            scale_factor,
+            crop,
+            elements,
+            screenshot,
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read(path)
+            .with_context(|| format!("Failed to read project: {}", path.display()))?;
+
+        let project = serde_json::from_slice::<Self>(&contents).context("Invalid project file")?;
+
+        if project.version > PROJECT_VERSION {
+            bail!(
+                "Project was saved by a newer version of Capter (format {})",
+                project.version
+            );
+        }
+
+        Ok(project)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let contents = serde_json::to_vec(self).context("Failed to encode project")?;
+
+        fs::write(path, contents)
+            .with_context(|| format!("Failed to save project: {}", path.display()))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Point")]
+pub struct PointDef {
+    x: f32,
+    y: f32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Size")]
+pub struct SizeDef {
+    width: f32,
+    height: f32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Vector")]
+pub struct VectorDef {
+    x: f32,
+    y: f32,
+}
+
+/// Serializes a list of points with [`PointDef`].
+pub mod points {
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct PointRef(#[serde(with = "PointDef")] Point);
+
+    pub fn serialize<S: Serializer>(points: &[Point], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(points.iter().copied().map(PointRef))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Point>, D::Error> {
+        Vec::<PointRef>::deserialize(deserializer)
+            .map(|points| points.into_iter().map(|point| point.0).collect())
+    }
+}
+
+mod png_base64 {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(image: &RgbaImage, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut png = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .map_err(serde::ser::Error::custom)?;
+
+        serializer.serialize_str(&BASE64_STANDARD.encode(png))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<RgbaImage, D::Error> {
+        let png = BASE64_STANDARD
+            .decode(String::deserialize(deserializer)?)
+            .map_err(serde::de::Error::custom)?;
+
+        load_from_memory_with_format(&png, ImageFormat::Png)
+            .map(|image| image.into_rgba8())
+            .map_err(serde::de::Error::custom)
+    }
+}
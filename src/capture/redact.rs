@@ -0,0 +1,96 @@
+use iced::Point;
+use xcap::image::{
+    Rgba,
+    RgbaImage,
+    imageops::{
+        crop_imm,
+        fast_blur,
+    },
+};
+
+use crate::capture::draw::DrawElement;
+
+/// Edge of a pixelation block per unit of size, in logical pixels.
+const PIXELATE_FACTOR: f32 = 4.0;
+
+/// Blur sigma per unit of size, in logical pixels.
+const BLUR_FACTOR: f32 = 3.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Effect {
+    Pixelate,
+    Blur,
+}
+
+/// Redacted pixels of the area covered by the element, with their position in `image`.
+///
+/// The element is in logical coordinates, `offset` is the position of `image` in physical ones.
+pub fn redacted(
+    image: &RgbaImage,
+    element: &DrawElement,
+    offset: Point,
+    scale_factor: f32,
+) -> Option<(u32, u32, RgbaImage)> {
+    let (effect, area) = element.tool.redaction()?;
+
+    let left = (area.x * scale_factor - offset.x).floor().max(0.0) as u32;
+    let top = (area.y * scale_factor - offset.y).floor().max(0.0) as u32;
+    let right = (((area.x + area.width) * scale_factor - offset.x)
+        .ceil()
+        .max(0.0) as u32)
+        .min(image.width());
+    let bottom = (((area.y + area.height) * scale_factor - offset.y)
+        .ceil()
+        .max(0.0) as u32)
+        .min(image.height());
+
+    if right <= left || bottom <= top {
+        return None;
+    }
+
+    let area = crop_imm(image, left, top, right - left, bottom - top).to_image();
+
+    let strength = element.size as f32 * scale_factor;
+
+    let area = match effect {
+        Effect::Pixelate => pixelate(&area, (strength * PIXELATE_FACTOR).round() as u32),
+        Effect::Blur => fast_blur(&area, strength * BLUR_FACTOR),
+    };
+
+    Some((left, top, area))
+}
+
+/// Fills each `block` sized square with its average color.
+fn pixelate(image: &RgbaImage, block: u32) -> RgbaImage {
+    let block = block.max(2);
+    let (width, height) = image.dimensions();
+    let mut pixelated = RgbaImage::new(width, height);
+
+    for block_y in (0..height).step_by(block as usize) {
+        for block_x in (0..width).step_by(block as usize) {
+            let (block_width, block_height) =
+                ((width - block_x).min(block), (height - block_y).min(block));
+
+            let mut sum = [0u64; 4];
+            for y in block_y..block_y + block_height {
+                for x in block_x..block_x + block_width {
+                    let pixel = image.get_pixel(x, y);
+                    sum.iter_mut()
+                        .zip(pixel.0)
+                        .for_each(|(sum, channel)| *sum += channel as u64);
+                }
+            }
+
+            let count = (block_width * block_height) as u64;
+            let average = Rgba(sum.map(|sum| (sum / count) as u8));
+
+            for y in block_y..block_y + block_height {
+                for x in block_x..block_x + block_width {
+                    pixelated.put_pixel(x, y, average);
+                }
+            }
+        }
+    }
+
+    pixelated
+}
@@ -0,0 +1,94 @@
+use iced::{
+    Point,
+    Rectangle,
+    Size,
+};
+
+use crate::capture::draw::DrawElement;
+
+/// Distance in which the cursor still grabs an element or a handle.
+pub const GRAB_TOLERANCE: f32 = 6.0;
+
+pub const HANDLE_SIZE: f32 = 8.0;
+
+#[derive(Debug, Default, Clone)]
+pub enum SelectState {
+    #[default]
+    Idle,
+    Moving {
+        start: Point,
+        original: DrawElement,
+    },
+    Resizing {
+        /// Corner opposite to the dragged handle, which stays in place.
+        anchor: Point,
+        original: DrawElement,
+    },
+    /// Dragging the control point of a curve.
+    Bending {
+        original: DrawElement,
+    },
+}
+
+impl SelectState {
+    pub fn is_idle(&self) -> bool {
+        matches!(self, Self::Idle)
+    }
+}
+
+/// Corners of the bounds, which act as resize handles.
+pub fn handles(bounds: Rectangle) -> [Point; 4] {
+    [
+        bounds.position(),
+        Point::new(bounds.x + bounds.width, bounds.y),
+        Point::new(bounds.x + bounds.width, bounds.y + bounds.height),
+        Point::new(bounds.x, bounds.y + bounds.height),
+    ]
+}
+
+/// Corner opposite to the handle under the cursor, if any.
+pub fn grabbed_handle(bounds: Rectangle, cursor: Point) -> Option<Point> {
+    let handles = handles(bounds);
+
+    handles
+        .iter()
+        .position(|handle| handle.distance(cursor) <= HANDLE_SIZE / 2.0 + GRAB_TOLERANCE)
+        .map(|index| handles[(index + 2) % 4])
+}
+
+/// Whether the cursor grabs the control point of a curved element.
+pub fn grabbed_control(element: &DrawElement, cursor: Point) -> bool {
+    element
+        .control()
+        .is_some_and(|control| control.distance(cursor) <= HANDLE_SIZE / 2.0 + GRAB_TOLERANCE)
+}
+
+/// Scales the element so its bounds span from `anchor` to `cursor`.
+pub fn resize(original: &DrawElement, anchor: Point, cursor: Point) -> DrawElement {
+    let bounds = original.bounds();
+
+    let target = Rectangle::new(
+        Point::new(anchor.x.min(cursor.x), anchor.y.min(cursor.y)),
+        Size::new((cursor.x - anchor.x).abs(), (cursor.y - anchor.y).abs()),
+    );
+
+    // Flat elements, like horizontal lines, keep their size along the flat axis
+    let scale = |target: f32, original: f32| match original > f32::EPSILON {
+        true => target / original,
+        false => 1.0,
+    };
+    let (scale_x, scale_y) = (
+        scale(target.width, bounds.width),
+        scale(target.height, bounds.height),
+    );
+
+    let mut element = original.clone();
+    element.tool.transform(|point| {
+        Point::new(
+            target.x + (point.x - bounds.x) * scale_x,
+            target.y + (point.y - bounds.y) * scale_y,
+        )
+    });
+
+    element
+}
@@ -0,0 +1,122 @@
+use std::{
+    f32::consts::FRAC_PI_4,
+    rc::Rc,
+};
+
+use iced::{
+    Point,
+    Rectangle,
+    Size,
+    Vector,
+    keyboard::Modifiers,
+};
+
+use crate::capture::{
+    CapturedWindow,
+    draw::Tool,
+};
+
+/// Distance within which points snap to window edges, in logical pixels.
+const SNAP_DISTANCE: f32 = 8.0;
+
+/// How a drag draws the shape, which sets the constraints applied to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Drag {
+    /// Spans a box between both points.
+    Box,
+    /// Runs from one point to the other.
+    Line,
+}
+
+impl Drag {
+    pub fn of(tool: &Tool) -> Option<Self> {
+        match tool {
+            Tool::Rectangle { .. }
+            | Tool::Ellipse { .. }
+            | Tool::Pixelate { .. }
+            | Tool::Blur { .. }
+            | Tool::Spotlight { .. } => Some(Self::Box),
+            Tool::Line { .. } | Tool::Arrow { .. } => Some(Self::Line),
+            _ => None,
+        }
+    }
+
+    /// Applies the modifiers to a drag from `start` to `end`.
+    ///
+    /// Shift keeps boxes square and lines at multiples of 45°, Alt grows around `start`.
+    pub fn constrain(self, start: Point, end: Point, modifiers: Modifiers) -> (Point, Point) {
+        let mut offset = end - start;
+
+        if modifiers.shift() {
+            offset = match self {
+                Drag::Box => {
+                    let side = offset.x.abs().max(offset.y.abs());
+                    Vector::new(side * offset.x.signum(), side * offset.y.signum())
+                }
+                Drag::Line => {
+                    let length = start.distance(end);
+                    let angle = (offset.y.atan2(offset.x) / FRAC_PI_4).round() * FRAC_PI_4;
+                    Vector::new(length * angle.cos(), length * angle.sin())
+                }
+            };
+        }
+
+        match modifiers.alt() {
+            true => (start - offset, start + offset),
+            false => (start, start + offset),
+        }
+    }
+}
+
+/// Moves the point onto the edges of windows close to it, unless Ctrl is held.
+pub fn snap(
+    point: Point,
+    windows: &[Rc<CapturedWindow>],
+    scale_factor: f32,
+    modifiers: Modifiers,
+) -> Point {
+    if modifiers.control() {
+        return point;
+    }
+
+    let windows = windows
+        .iter()
+        .map(|window| {
+            Rectangle::new(
+                Point::new(window.x, window.y),
+                Size::new(window.width, window.height),
+            ) * (1.0 / scale_factor)
+        })
+        .collect::<Vec<_>>();
+
+    let alongside = |value: f32, start: f32, length: f32| {
+        (start - SNAP_DISTANCE..=start + length + SNAP_DISTANCE).contains(&value)
+    };
+    let nearest = |value: f32, edges: Vec<f32>| {
+        edges
+            .into_iter()
+            .filter(|edge| (edge - value).abs() <= SNAP_DISTANCE)
+            .min_by(|a, b| (a - value).abs().total_cmp(&(b - value).abs()))
+            .unwrap_or(value)
+    };
+
+    // Edges only attract points alongside them
+    let x = nearest(
+        point.x,
+        windows
+            .iter()
+            .filter(|window| alongside(point.y, window.y, window.height))
+            .flat_map(|window| [window.x, window.x + window.width])
+            .collect(),
+    );
+    let y = nearest(
+        point.y,
+        windows
+            .iter()
+            .filter(|window| alongside(point.x, window.x, window.width))
+            .flat_map(|window| [window.y, window.y + window.height])
+            .collect(),
+    );
+
+    Point::new(x, y)
+}
@@ -0,0 +1,67 @@
+use iced::Point;
+use xcap::image::{
+    Rgba,
+    RgbaImage,
+};
+
+use crate::capture::draw::DrawElement;
+
+/// Opacity of the shade per unit of size.
+const DIM_FACTOR: f32 = 0.15;
+
+/// Shade darkening everything outside the spotlights, `None` without any.
+///
+/// The elements are in logical coordinates, `offset` is the position of the shade in physical
+/// ones.
+pub fn shade(
+    elements: &[DrawElement],
+    width: u32,
+    height: u32,
+    offset: Point,
+    scale_factor: f32,
+) -> Option<RgbaImage> {
+    let spotlights = elements
+        .iter()
+        .filter_map(|element| {
+            let (area, elliptical) = element.tool.spotlight()?;
+            Some((area, elliptical, element.size))
+        })
+        .collect::<Vec<_>>();
+
+    // The darkest spotlight sets the shade for all of them
+    let opacity = spotlights
+        .iter()
+        .map(|(_, _, size)| *size as f32 * DIM_FACTOR)
+        .reduce(f32::max)?
+        .min(1.0);
+    let shade = Rgba([0, 0, 0, (opacity * 255.0).round() as u8]);
+
+    let lit = |point: Point| {
+        spotlights
+            .iter()
+            .any(|(area, elliptical, _)| match elliptical {
+                true => {
+                    let center = area.center();
+                    let (dx, dy) = (
+                        (point.x - center.x) / (area.width / 2.0),
+                        (point.y - center.y) / (area.height / 2.0),
+                    );
+
+                    dx * dx + dy * dy <= 1.0
+                }
+                false => area.contains(point),
+            })
+    };
+
+    Some(RgbaImage::from_fn(width, height, |x, y| {
+        let point = Point::new(
+            (x as f32 + 0.5 + offset.x) / scale_factor,
+            (y as f32 + 0.5 + offset.y) / scale_factor,
+        );
+
+        match lit(point) {
+            true => Rgba([0, 0, 0, 0]),
+            false => shade,
+        }
+    }))
+}
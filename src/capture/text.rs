@@ -0,0 +1,201 @@
+use std::{
+    collections::BTreeSet,
+    fmt::Display,
+    sync::{
+        Arc,
+        LazyLock,
+    },
+};
+
+use iced::{
+    Font,
+    Point,
+    Rectangle,
+    Size,
+    Vector,
+    font::{
+        Family,
+        Stretch,
+        Style,
+        Weight,
+    },
+};
+use resvg::usvg::fontdb::Database;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::consts::{
+    BOLD_FONT_TTF,
+    FONT_NAME,
+    MEDIUM_FONT_TTF,
+};
+
+/// Approximate width of a character relative to the font size.
+const TEXT_WIDTH_FACTOR: f32 = 0.6;
+
+/// Distance between baselines relative to the font size.
+const LINE_HEIGHT_FACTOR: f32 = 1.2;
+
+/// Space between the text and its background box relative to the font size.
+const PADDING_FACTOR: f32 = 0.25;
+
+/// Radius of the callout corners relative to the font size.
+const CORNER_RADIUS_FACTOR: f32 = 0.4;
+
+/// Width of the callout tail at its base relative to the font size.
+const TAIL_WIDTH_FACTOR: f32 = 0.8;
+
+/// Bundled fonts followed by the system fonts, shared by every rendering of annotations.
+pub static FONT_DATABASE: LazyLock<Arc<Database>> = LazyLock::new(|| {
+    let mut database = Database::new();
+    database.load_font_data(MEDIUM_FONT_TTF.to_vec());
+    database.load_font_data(BOLD_FONT_TTF.to_vec());
+    database.load_system_fonts();
+    Arc::new(database)
+});
+
+/// Font families available for text, the bundled one first.
+pub static FONT_FAMILIES: LazyLock<Vec<String>> = LazyLock::new(|| {
+    let system_families = FONT_DATABASE
+        .faces()
+        .flat_map(|face| face.families.iter().map(|(family, _)| family.clone()))
+        .filter(|family| family != FONT_NAME)
+        .collect::<BTreeSet<_>>();
+
+    std::iter::once(FONT_NAME.to_string())
+        .chain(system_families)
+        .collect()
+});
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TextStyle {
+    pub bold: bool,
+    pub italic: bool,
+    pub background: TextBackground,
+    /// Font family, the bundled font if unset.
+    pub font: Option<String>,
+}
+
+impl TextStyle {
+    pub fn family(&self) -> &'static str {
+        self.font
+            .as_deref()
+            .and_then(|font| FONT_FAMILIES.iter().find(|family| *family == font))
+            .map_or(FONT_NAME, String::as_str)
+    }
+
+    pub fn font(&self) -> Font {
+        Font {
+            family: Family::Name(self.family()),
+            weight: match self.bold {
+                true => Weight::Bold,
+                false => Weight::Medium,
+            },
+            stretch: Stretch::Normal,
+            style: match self.italic {
+                true => Style::Italic,
+                false => Style::Normal,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TextBackground {
+    #[default]
+    None,
+    /// Box in the contrasting color below the text.
+    Filled,
+    /// Box outline in the text color.
+    Outline,
+}
+
+impl TextBackground {
+    pub const ALL: [TextBackground; 3] = [Self::None, Self::Filled, Self::Outline];
+}
+
+impl Display for TextBackground {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TextBackground::None => write!(f, "No Box"),
+            TextBackground::Filled => write!(f, "Filled Box"),
+            TextBackground::Outline => write!(f, "Outlined Box"),
+        }
+    }
+}
+
+/// Placement of text anchored at the vertical center of its first line.
+pub struct TextLayout<'a> {
+    pub font_size: f32,
+    /// Each line with the middle of its left edge.
+    pub lines: Vec<(&'a str, Point)>,
+    /// Area of the text, padded for the background box.
+    pub bounds: Rectangle,
+}
+
+impl<'a> TextLayout<'a> {
+    pub fn new(anchor: Point, text: &'a str, font_size: f32) -> Self {
+        let line_height = font_size * LINE_HEIGHT_FACTOR;
+        let padding = font_size * PADDING_FACTOR;
+
+        let lines = text
+            .split('\n')
+            .enumerate()
+            .map(|(index, line)| {
+                (
+                    line,
+                    Point::new(anchor.x, anchor.y + index as f32 * line_height),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let width = lines
+            .iter()
+            .map(|(line, _)| line.chars().count())
+            .max()
+            .unwrap_or_default() as f32
+            * font_size
+            * TEXT_WIDTH_FACTOR;
+
+        let bounds = Rectangle::new(
+            Point::new(anchor.x - padding, anchor.y - line_height / 2.0 - padding),
+            Size::new(
+                width + padding * 2.0,
+                lines.len() as f32 * line_height + padding * 2.0,
+            ),
+        );
+
+        Self {
+            font_size,
+            lines,
+            bounds,
+        }
+    }
+
+    pub fn corner_radius(&self) -> f32 {
+        self.font_size * CORNER_RADIUS_FACTOR
+    }
+
+    /// Triangle of a callout tail, from the center of the box to `target`.
+    ///
+    /// The base lies inside the box, so only the part sticking out of it shows.
+    pub fn tail(&self, target: Point) -> [Point; 3] {
+        let center = self.bounds.center();
+        let length = center.distance(target);
+
+        if length == 0.0 {
+            return [center; 3];
+        }
+
+        let half_width = self.font_size * TAIL_WIDTH_FACTOR / 2.0;
+        let normal = Vector::new(
+            (center.y - target.y) / length * half_width,
+            (target.x - center.x) / length * half_width,
+        );
+
+        [center + normal, target, center - normal]
+    }
+}
@@ -1,7 +1,14 @@
 use iced::{
     Point,
+    Rectangle,
     Size,
-    widget::operation::focus,
+    Vector,
+    clipboard,
+    keyboard::Modifiers,
+    widget::{
+        operation::focus,
+        text_editor,
+    },
 };
 
 use crate::{
@@ -10,12 +17,40 @@ use crate::{
         Capture,
         Message,
         Request,
-        crop::CropState,
+        crop::{
+            CropPreset,
+            CropState,
+            Grip,
+            SizeInput,
+            move_within,
+        },
         draw::{
             DrawElement,
             DrawState,
+            ToolColor,
         },
+        history::{
+            Command,
+            Style,
+        },
+        line::LineStyle,
         mode::Mode,
+        picker::{
+            ColorPicker,
+            PALETTE_LIMIT,
+        },
+        select::{
+            GRAB_TOLERANCE,
+            SelectState,
+            grabbed_control,
+            grabbed_handle,
+            resize,
+        },
+        snap::{
+            Drag,
+            snap,
+        },
+        text::TextStyle,
     },
 };
 
@@ -26,49 +61,94 @@ impl Capture {
                 self.toolbar_at_top = !self.toolbar_at_top;
             }
             Message::Undo => {
-                if self.mode.is_draw_mode() {
-                    self.elements.pop();
-                    self.cache.clear();
-                }
+                self.size_input = None;
+                self.undo();
+            }
+            Message::Redo => {
+                self.size_input = None;
+                self.redo();
             }
             Message::Done => match &self.mode {
                 Mode::Draw { element: shape, .. } => {
                     if shape.tool.is_text_tool() {
                         self.push_shape();
                     }
-                    self.mode = Mode::default();
-                    self.mode.get_window_below_cursor(
-                        &self.windows,
-                        &self.cursor_position,
-                        self.scale_factor,
-                        self.screenshot.dimensions(),
-                    );
+                    self.enter_crop_mode();
+                }
+                Mode::Select { .. } => {
+                    self.enter_crop_mode();
                 }
                 Mode::Crop { .. } => {
                     return Action::requests([Request::Close]);
                 }
             },
+            Message::Cancel if self.eyedropper => {
+                self.eyedropper = false;
+            }
+            Message::Cancel if self.color_picker.is_some() => {
+                self.color_picker = None;
+            }
             Message::Cancel => match &mut self.mode {
                 Mode::Draw { .. } => {
-                    self.elements.clear();
+                    if !self.elements.is_empty() {
+                        let elements = std::mem::take(&mut self.elements);
+                        self.record(Command::Clear { elements });
+                    }
                     self.cache.clear();
-                    self.mode = Mode::default();
-                    self.mode.get_window_below_cursor(
-                        &self.windows,
-                        &self.cursor_position,
-                        self.scale_factor,
-                        self.screenshot.dimensions(),
-                    );
+                    self.enter_crop_mode();
+                }
+                Mode::Select {
+                    selected: selected @ Some(_),
+                    ..
+                } => {
+                    *selected = None;
+                }
+                Mode::Select { selected: None, .. } => {
+                    self.enter_crop_mode();
                 }
                 Mode::Crop { state: status, .. } => {
                     *status = CropState::None;
                     return Action::requests([Request::Close]);
                 }
             },
+            Message::ChangeOutputFormat(output_format) => {
+                self.output_format = output_format;
+            }
+            Message::ChangeCropPreset(preset) => {
+                self.crop_preset = preset;
+            }
+            Message::Nudge(offset) => {
+                // Steps are in physical pixels, like the size typed in the toolbar
+                let offset = offset * (1.0 / self.scale_factor);
+                self.adjust_crop(|area, screen| move_within(area, offset, screen));
+            }
+            Message::CopyColor => {
+                // Only while selecting, where the loupe shows the color
+                if let Mode::Crop { .. } = self.mode
+                    && let Some(color) = self.color_at(self.cursor_position)
+                {
+                    return clipboard::write(color.as_hex()).into();
+                }
+            }
+            Message::ChangeCropWidth(width) => {
+                self.edit_size_input(|input| input.width = width);
+            }
+            Message::ChangeCropHeight(height) => {
+                self.edit_size_input(|input| input.height = height);
+            }
+            Message::SubmitCropSize => {
+                self.submit_size_input();
+            }
             Message::ChangeTool(tool) => {
                 self.push_shape();
                 if let Mode::Draw { element: shape, .. } = &mut self.mode {
+                    let before = shape.clone();
                     shape.tool = tool;
+
+                    if before.tool != shape.tool {
+                        let after = shape.clone();
+                        self.record(Command::Template { before, after });
+                    }
                 } else {
                     self.mode = Mode::Draw {
                         element: DrawElement {
@@ -79,31 +159,183 @@ impl Capture {
                     }
                 }
             }
+            Message::Select => {
+                self.push_shape();
+                self.mode = Mode::Select {
+                    selected: None,
+                    state: SelectState::Idle,
+                };
+            }
+            Message::Delete => {
+                if let Mode::Select { selected, .. } = &mut self.mode
+                    && let Some(index) = selected.take()
+                {
+                    let element = self.elements.remove(index);
+                    self.record(Command::Remove { index, element });
+                    self.cache.clear();
+                }
+            }
             Message::ChangeSize(stroke_width) => {
+                self.history.begin_drag();
+                if let Mode::Select {
+                    selected: Some(index),
+                    ..
+                } = self.mode
+                {
+                    let element = &mut self.elements[index];
+                    let before = Style::of(element);
+                    element.size = stroke_width;
+                    let after = Style::of(element);
+
+                    if before != after {
+                        self.record(Command::Restyle {
+                            index,
+                            before,
+                            after,
+                        });
+                    }
+                    self.cache.clear();
+                    return Action::none();
+                }
+
                 self.push_shape();
                 if let Mode::Draw {
                     element: shape,
                     state: status,
                 } = &mut self.mode
                 {
+                    let before = shape.clone();
                     shape.size = stroke_width;
                     *status = DrawState::Idle;
+
+                    if before.size != shape.size {
+                        let after = shape.clone();
+                        self.record(Command::Template { before, after });
+                    }
                 }
             }
             Message::ChangeColor(color) => {
-                self.push_shape();
-                if let Mode::Draw {
-                    element: shape,
-                    state: status,
-                } = &mut self.mode
-                {
-                    shape.color = color;
-                    *status = DrawState::Idle;
+                self.set_color(color);
+            }
+            Message::ToggleColorPicker => {
+                self.color_picker = match self.color_picker {
+                    Some(_) => None,
+                    None => self.current_color().map(ColorPicker::new),
+                };
+            }
+            Message::PickHueSaturation(hue, saturation) => {
+                self.history.begin_drag();
+                if let Some(picker) = &mut self.color_picker {
+                    picker.hue = hue;
+                    picker.saturation = saturation;
+                    // Picking on a black color would have no visible effect
+                    if picker.value == 0.0 {
+                        picker.value = 1.0;
+                    }
+                    picker.sync_hex();
+                    let color = picker.color();
+                    self.set_color(color);
+                }
+            }
+            Message::PickValue(value) => {
+                self.history.begin_drag();
+                if let Some(picker) = &mut self.color_picker {
+                    picker.value = value;
+                    picker.sync_hex();
+                    let color = picker.color();
+                    self.set_color(color);
+                }
+            }
+            Message::PickAlpha(alpha) => {
+                self.history.begin_drag();
+                if let Some(picker) = &mut self.color_picker {
+                    picker.alpha = alpha;
+                    picker.sync_hex();
+                    let color = picker.color();
+                    self.set_color(color);
+                }
+            }
+            Message::EndDrag => {
+                self.history.end_drag();
+            }
+            Message::UpdateHex(hex) => {
+                if let Some(picker) = &mut self.color_picker {
+                    let color = ToolColor::from_hex(&hex);
+                    if let Some(color) = color {
+                        picker.set_color(color);
+                    }
+                    // Keeps the text as typed
+                    picker.hex = hex;
+
+                    if let Some(color) = color {
+                        self.set_color(color);
+                    }
+                }
+            }
+            Message::ToggleFavorite => {
+                if let Some(color) = self.current_color() {
+                    match self.palette.iter().position(|favorite| *favorite == color) {
+                        Some(index) => {
+                            self.palette.remove(index);
+                        }
+                        None => {
+                            self.palette.push(color);
+                            if self.palette.len() > PALETTE_LIMIT {
+                                self.palette.remove(0);
+                            }
+                        }
+                    }
+                    return Action::requests([Request::SavePalette(self.palette.clone())]);
                 }
             }
-            Message::UpdateText(text) => {
+            Message::ToggleEyedropper => {
+                self.eyedropper = !self.eyedropper;
+            }
+            Message::EditText(action) => {
                 if let Mode::Draw { element: shape, .. } = &mut self.mode {
-                    shape.tool.update_text(text);
+                    self.text_content.perform(action);
+                    let text = self.text_content.text();
+                    shape
+                        .tool
+                        .update_text(text.trim_end_matches('\n').to_string());
+                }
+            }
+            Message::CommitText => {
+                self.push_shape();
+            }
+            Message::ToggleBold => {
+                return self.set_text_style(|style| style.bold = !style.bold);
+            }
+            Message::ToggleItalic => {
+                return self.set_text_style(|style| style.italic = !style.italic);
+            }
+            Message::ChangeTextBackground(background) => {
+                return self.set_text_style(|style| style.background = background);
+            }
+            Message::ChangeFont(font) => {
+                return self.set_text_style(|style| style.font = Some(font));
+            }
+            Message::ChangeDash(dash) => {
+                self.set_line_style(|style| style.dash = dash);
+            }
+            Message::ToggleFilledHead => {
+                self.set_line_style(|style| style.filled_head = !style.filled_head);
+            }
+            Message::ToggleDoubleHead => {
+                self.set_line_style(|style| style.double_head = !style.double_head);
+            }
+            Message::ToggleCurved => {
+                self.set_line_style(|style| style.curved = !style.curved);
+            }
+            Message::ToggleConnector => {
+                self.set_line_style(|style| style.connector = !style.connector);
+            }
+            // The eyedropper samples on release, so the press draws nothing
+            Message::MousePressed if self.eyedropper => {}
+            Message::MouseReleased if self.eyedropper => {
+                self.eyedropper = false;
+                if let Some(color) = self.color_at(self.cursor_position) {
+                    self.set_color(color);
                 }
             }
             Message::MousePressed => match &mut self.mode {
@@ -113,33 +345,117 @@ impl Capture {
                     size,
                     state: status,
                 } => {
-                    *top_left = self.cursor_position;
-                    *bottom_right = self.cursor_position;
+                    self.size_input = None;
+
+                    let area = Rectangle::new(*top_left, *size);
+                    if let CropState::Area = status
+                        && let Some(grip) = Grip::at(area, self.cursor_position)
+                    {
+                        *status = CropState::Adjusting {
+                            grip,
+                            start: self.cursor_position,
+                            original: area,
+                        };
+                        return Action::none();
+                    }
+
+                    let previous = matches!(status, CropState::Area).then_some(area);
+
+                    let start = snap(
+                        self.cursor_position,
+                        &self.windows,
+                        self.scale_factor,
+                        self.modifiers,
+                    );
+
+                    *top_left = start;
+                    *bottom_right = start;
                     *size = Size::ZERO;
                     *status = CropState::InProgress {
-                        start: self.cursor_position,
-                        end: self.cursor_position,
+                        start,
+                        end: start,
+                        previous,
                     };
+
+                    // Fixed sizes are selected right away
+                    return self.update(Message::MouseMoved(self.cursor_position));
                 }
                 Mode::Draw {
                     element: shape,
                     state: status,
                 } => {
-                    if shape.tool.is_text_tool() && shape.tool.is_valid() {
-                        self.elements.push(shape.clone());
-                        self.cache.clear();
-                        shape.tool.reset();
+                    if shape.tool.is_text_tool() {
+                        if shape.tool.is_valid() {
+                            self.history.push_element(&mut self.elements, shape.clone());
+                            self.cache.clear();
+                            shape.tool.reset();
+                        }
+                        self.text_content = text_editor::Content::new();
                     }
 
-                    shape.tool.initiate(self.cursor_position);
+                    let start = match Drag::of(&shape.tool) {
+                        Some(_) => snap(
+                            self.cursor_position,
+                            &self.windows,
+                            self.scale_factor,
+                            self.modifiers,
+                        ),
+                        None => self.cursor_position,
+                    };
+
+                    shape.tool.initiate(start);
                     *status = DrawState::InProgress {
-                        initial_pt: self.cursor_position,
-                        final_pt: self.cursor_position,
+                        initial_pt: start,
+                        final_pt: start,
                     };
                 }
+                Mode::Select { selected, state } => {
+                    let cursor = self.cursor_position;
+
+                    // Handles of the selection take precedence over elements below them
+                    if let Some(index) = selected.filter(|index| {
+                        self.elements
+                            .get(*index)
+                            .is_some_and(|element| grabbed_control(element, cursor))
+                    }) {
+                        *state = SelectState::Bending {
+                            original: self.elements[index].clone(),
+                        };
+                    } else if let Some((index, anchor)) = selected.and_then(|index| {
+                        let element = self
+                            .elements
+                            .get(index)
+                            .filter(|element| element.tool.is_resizable())?;
+
+                        grabbed_handle(element.bounds(), cursor).map(|anchor| (index, anchor))
+                    }) {
+                        *state = SelectState::Resizing {
+                            anchor,
+                            original: self.elements[index].clone(),
+                        };
+                    } else {
+                        *selected = self
+                            .elements
+                            .iter()
+                            .rposition(|element| element.contains(cursor, GRAB_TOLERANCE));
+
+                        if let Some(index) = selected {
+                            *state = SelectState::Moving {
+                                start: cursor,
+                                original: self.elements[*index].clone(),
+                            };
+                        }
+                    }
+                }
             },
+            // Constraints apply right away, without waiting for the cursor to move
+            Message::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers;
+                return self.update(Message::MouseMoved(self.cursor_position));
+            }
             Message::MouseMoved(position) => {
                 self.cursor_position = position;
+                let screen = self.screen_size();
                 match &mut self.mode {
                     Mode::Crop {
                         top_left,
@@ -155,12 +471,48 @@ impl Capture {
                                 self.screenshot.dimensions(),
                             );
                         }
-                        CropState::InProgress { start, end } => {
-                            *end = position;
-                            *top_left = Point::new(start.x.min(end.x), start.y.min(end.y));
-                            *bottom_right = Point::new(start.x.max(end.x), start.y.max(end.y));
-                            *size =
-                                Size::new(bottom_right.x - top_left.x, bottom_right.y - top_left.y);
+                        CropState::InProgress { start, end, .. } => {
+                            *end = snap(position, &self.windows, self.scale_factor, self.modifiers);
+
+                            // Presets already set the shape, which Shift would change
+                            let modifiers = match self.crop_preset {
+                                CropPreset::Free => self.modifiers,
+                                _ => self.modifiers.difference(Modifiers::SHIFT),
+                            };
+                            let preset_end =
+                                self.crop_preset.constrain(*start, *end, self.scale_factor);
+                            let (from, to) = match self.crop_preset {
+                                // Alt centers fixed sizes on the start instead of doubling them
+                                CropPreset::Size(..) if modifiers.alt() => {
+                                    let half = (preset_end - *start) * 0.5;
+                                    (*start - half, *start + half)
+                                }
+                                _ => Drag::Box.constrain(*start, preset_end, modifiers),
+                            };
+
+                            let mut area = Rectangle::new(
+                                Point::new(from.x.min(to.x), from.y.min(to.y)),
+                                Size::new((to.x - from.x).abs(), (to.y - from.y).abs()),
+                            );
+                            // Fixed sizes move back on screen instead of getting cut
+                            if let CropPreset::Size(..) = self.crop_preset {
+                                area = move_within(area, Vector::ZERO, screen);
+                            }
+
+                            *top_left = area.position();
+                            *bottom_right = Point::new(area.x + area.width, area.y + area.height);
+                            *size = area.size();
+                        }
+                        CropState::Adjusting {
+                            grip,
+                            start,
+                            original,
+                        } => {
+                            let area = grip.drag(*original, position - *start, screen);
+
+                            *top_left = area.position();
+                            *bottom_right = Point::new(area.x + area.width, area.y + area.height);
+                            *size = area.size();
                         }
                         _ => {}
                     },
@@ -168,24 +520,71 @@ impl Capture {
                         element: shape,
                         state: status,
                     } => {
-                        if shape.tool.is_text_tool() {
-                            return Action::none();
-                        };
                         if let DrawState::InProgress {
                             initial_pt,
                             final_pt,
                         } = status
                         {
                             *final_pt = position;
-                            shape.tool.update(*initial_pt, *final_pt);
+
+                            match Drag::of(&shape.tool) {
+                                Some(drag) => {
+                                    let end = snap(
+                                        position,
+                                        &self.windows,
+                                        self.scale_factor,
+                                        self.modifiers,
+                                    );
+                                    let (start, end) =
+                                        drag.constrain(*initial_pt, end, self.modifiers);
+
+                                    shape.tool.update(start, end);
+                                }
+                                None => shape.tool.update(*initial_pt, *final_pt),
+                            }
                         }
                     }
+                    Mode::Select {
+                        selected: Some(index),
+                        state,
+                    } => match state {
+                        SelectState::Moving { start, original } => {
+                            let offset = position - *start;
+                            let mut element = original.clone();
+                            element.tool.transform(|point| point + offset);
+
+                            self.elements[*index] = element;
+                            self.cache.clear();
+                        }
+                        SelectState::Resizing { anchor, original } => {
+                            self.elements[*index] = resize(original, *anchor, position);
+                            self.cache.clear();
+                        }
+                        SelectState::Bending { original } => {
+                            let mut element = original.clone();
+                            element.tool.set_control(position);
+
+                            self.elements[*index] = element;
+                            self.cache.clear();
+                        }
+                        SelectState::Idle => {}
+                    },
+                    Mode::Select { selected: None, .. } => {}
                 }
             }
             Message::MouseReleased => match &mut self.mode {
-                Mode::Crop { state: status, .. } => {
-                    if let CropState::InProgress { start, end } = status {
-                        if start != end {
+                Mode::Crop {
+                    top_left,
+                    size,
+                    state: status,
+                    ..
+                } => {
+                    if let CropState::InProgress { previous, .. } = status {
+                        let before = *previous;
+                        // Areas of fixed sizes are selected by a click too
+                        let after = (*size != Size::ZERO).then(|| Rectangle::new(*top_left, *size));
+
+                        if after.is_some() {
                             *status = CropState::Area;
                         } else {
                             self.mode.get_window_below_cursor(
@@ -195,6 +594,18 @@ impl Capture {
                                 self.screenshot.dimensions(),
                             );
                         }
+
+                        if before != after {
+                            self.record(Command::Crop { before, after });
+                        }
+                    } else if let CropState::Adjusting { original, .. } = status {
+                        let before = Some(*original);
+                        let after = Some(Rectangle::new(*top_left, *size));
+                        *status = CropState::Area;
+
+                        if before != after {
+                            self.record(Command::Crop { before, after });
+                        }
                     }
                 }
                 Mode::Draw {
@@ -206,18 +617,279 @@ impl Capture {
                         return focus("TextInput").into();
                     } else {
                         if shape.tool.is_valid() {
-                            self.elements.push(shape.clone());
+                            self.history.push_element(&mut self.elements, shape.clone());
                             self.cache.clear();
                             shape.tool.reset();
                         }
                         *status = DrawState::Idle;
                     }
                 }
+                Mode::Select { selected, state } => {
+                    if let (
+                        Some(index),
+                        SelectState::Moving { original, .. }
+                        | SelectState::Resizing { original, .. }
+                        | SelectState::Bending { original },
+                    ) = (*selected, std::mem::take(state))
+                        && (original.bounds() != self.elements[index].bounds()
+                            || original.control() != self.elements[index].control())
+                    {
+                        let after = self.elements[index].clone();
+                        self.record(Command::Edit {
+                            index,
+                            before: original,
+                            after,
+                        });
+                    }
+                }
             },
         }
         Action::none()
     }
 
+    fn enter_crop_mode(&mut self) {
+        self.mode = Mode::default();
+        self.mode.get_window_below_cursor(
+            &self.windows,
+            &self.cursor_position,
+            self.scale_factor,
+            self.screenshot.dimensions(),
+        );
+    }
+
+    /// Changes the selected area, `change` getting it with the size of the screen.
+    fn adjust_crop(&mut self, change: impl FnOnce(Rectangle, Size) -> Rectangle) {
+        let screen = self.screen_size();
+
+        if let Mode::Crop {
+            top_left,
+            bottom_right,
+            size,
+            state: CropState::Area,
+        } = &mut self.mode
+        {
+            let before = Rectangle::new(*top_left, *size);
+            let after = change(before, screen);
+
+            if before != after {
+                *top_left = after.position();
+                *bottom_right = Point::new(after.x + after.width, after.y + after.height);
+                *size = after.size();
+
+                self.record(Command::Crop {
+                    before: Some(before),
+                    after: Some(after),
+                });
+            }
+        }
+    }
+
+    /// Changes the size typed in the toolbar, which applies once submitted.
+    fn edit_size_input(&mut self, change: impl FnOnce(&mut SizeInput)) {
+        let Mode::Crop {
+            size,
+            state: CropState::Area,
+            ..
+        } = &self.mode
+        else {
+            return;
+        };
+
+        let mut input = self
+            .size_input
+            .take()
+            .unwrap_or_else(|| SizeInput::of(*size, self.scale_factor));
+        change(&mut input);
+
+        self.size_input = Some(input);
+    }
+
+    /// Resizes the selected area to the size typed in the toolbar, if it is valid.
+    fn submit_size_input(&mut self) {
+        let Some(size) = self
+            .size_input
+            .as_ref()
+            .and_then(|input| input.size(self.scale_factor))
+        else {
+            return;
+        };
+
+        // Grows from the top left corner, moving back when reaching the screen edges
+        self.adjust_crop(|area, screen| {
+            move_within(
+                Rectangle::new(area.position(), size.min(screen)),
+                Vector::ZERO,
+                screen,
+            )
+        });
+
+        // Shows the size applied, which the screen may have limited
+        self.size_input = None;
+    }
+
+    /// Applies the color to the selected element, or to the elements drawn next.
+    fn set_color(&mut self, color: ToolColor) {
+        if let Some(picker) = &mut self.color_picker
+            && picker.color() != color
+        {
+            picker.set_color(color);
+        }
+
+        if let Mode::Select {
+            selected: Some(index),
+            ..
+        } = self.mode
+        {
+            let element = &mut self.elements[index];
+            let before = Style::of(element);
+            element.color = color;
+            let after = Style::of(element);
+
+            if before != after {
+                self.record(Command::Restyle {
+                    index,
+                    before,
+                    after,
+                });
+            }
+            self.cache.clear();
+            return;
+        }
+
+        self.push_shape();
+        if let Mode::Draw {
+            element: shape,
+            state: status,
+        } = &mut self.mode
+        {
+            let before = shape.clone();
+            shape.color = color;
+            *status = DrawState::Idle;
+
+            if before.color != shape.color {
+                let after = shape.clone();
+                self.record(Command::Template { before, after });
+            }
+        }
+    }
+
+    /// Applies the text style change to the selected element, or to the text being written.
+    fn set_text_style(&mut self, change: impl FnOnce(&mut TextStyle)) -> Action<Message, Request> {
+        if let Mode::Select {
+            selected: Some(index),
+            ..
+        } = self.mode
+        {
+            let element = &mut self.elements[index];
+            let before = Style::of(element);
+            change(&mut element.text_style);
+            let after = Style::of(element);
+
+            if before != after {
+                self.record(Command::Restyle {
+                    index,
+                    before,
+                    after,
+                });
+            }
+            self.cache.clear();
+            return Action::none();
+        }
+
+        // Unlike other styles, the text being written stays open so it can be styled as a whole
+        if let Mode::Draw {
+            element: shape,
+            state: status,
+        } = &mut self.mode
+        {
+            let before = shape.clone();
+            change(&mut shape.text_style);
+            let waiting_for_input = status.is_waiting_for_input();
+
+            if before.text_style != shape.text_style {
+                let after = shape.clone();
+                self.record(Command::Template { before, after });
+            }
+
+            if waiting_for_input {
+                return focus("TextInput").into();
+            }
+        }
+        Action::none()
+    }
+
+    /// Applies the line style change to the selected element, or to the elements drawn next.
+    fn set_line_style(&mut self, change: impl FnOnce(&mut LineStyle)) {
+        if let Mode::Select {
+            selected: Some(index),
+            ..
+        } = self.mode
+        {
+            let element = &mut self.elements[index];
+            let before = Style::of(element);
+            change(&mut element.line_style);
+            let after = Style::of(element);
+
+            // Curves start evenly bent, whatever their control point was before
+            if after.line_style.curved && !before.line_style.curved {
+                element.tool.reset_control();
+            }
+
+            if before != after {
+                self.record(Command::Restyle {
+                    index,
+                    before,
+                    after,
+                });
+            }
+            self.cache.clear();
+            return;
+        }
+
+        self.push_shape();
+        if let Mode::Draw { element: shape, .. } = &mut self.mode {
+            let before = shape.clone();
+            change(&mut shape.line_style);
+
+            if before.line_style != shape.line_style {
+                let after = shape.clone();
+                self.record(Command::Template { before, after });
+            }
+        }
+    }
+
+    /// Color of the selected element, or of the elements drawn next.
+    fn current_color(&self) -> Option<ToolColor> {
+        match &self.mode {
+            Mode::Draw { element, .. } => Some(element.color),
+            Mode::Select {
+                selected: Some(index),
+                ..
+            } => self.elements.get(*index).map(|element| element.color),
+            _ => None,
+        }
+    }
+
+    /// Size of the screenshot in logical pixels.
+    fn screen_size(&self) -> Size {
+        let (width, height) = self.screenshot.dimensions();
+
+        Size::new(
+            width as f32 / self.scale_factor,
+            height as f32 / self.scale_factor,
+        )
+    }
+
+    /// Color of the screenshot pixel at the point.
+    pub(super) fn color_at(&self, point: Point) -> Option<ToolColor> {
+        let pixel = self.screenshot.get_pixel_checked(
+            (point.x * self.scale_factor) as u32,
+            (point.y * self.scale_factor) as u32,
+        )?;
+
+        Some(ToolColor::rgb(pixel[0], pixel[1], pixel[2]))
+    }
+
     fn push_shape(&mut self) {
         if let Mode::Draw {
             element: shape,
@@ -225,11 +897,12 @@ impl Capture {
         } = &mut self.mode
         {
             if shape.tool.is_valid() {
-                self.elements.push(shape.clone());
+                self.history.push_element(&mut self.elements, shape.clone());
             }
             shape.tool.reset();
             *status = DrawState::Idle;
         }
+        self.text_content = text_editor::Content::new();
         self.cache.clear();
     }
 }
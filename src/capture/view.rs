@@ -2,20 +2,30 @@ use iced::{
     Alignment::{
         self,
     },
+    Font,
     Length,
+    Size,
+    font,
+    keyboard::{
+        Key,
+        key::Named,
+    },
     widget::{
         Button,
         Canvas,
         Column,
         Container,
         Image,
+        PickList,
         Row,
         Slider,
         Stack,
         Text,
+        TextEditor,
         TextInput,
         Tooltip,
         opaque,
+        text_editor::Binding,
         tooltip::Position,
     },
 };
@@ -24,18 +34,40 @@ use crate::{
     capture::{
         Capture,
         Message,
-        crop::CropState,
+        crop::{
+            CropState,
+            SizeInput,
+        },
         draw::{
             Tool,
             ToolColor,
         },
+        line::{
+            Dash,
+            LineStyle,
+        },
         mode::Mode,
+        picker::HueSaturationField,
+        text::{
+            FONT_FAMILIES,
+            TextBackground,
+            TextStyle,
+        },
     },
     consts::{
+        BOLD_FONT,
+        COLOR_PICKER_ICON,
+        CONNECTOR_ICON,
+        CURVED_ICON,
+        DOUBLE_HEAD_ICON,
+        EYEDROPPER_ICON,
+        FILLED_HEAD_ICON,
         ICON_FONT,
         MEDIUM_FONT,
         MOVE_ICON,
+        SELECT_ICON,
     },
+    output_format::OutputFormat,
     theme::{
         Element,
         button::ButtonClass,
@@ -48,7 +80,8 @@ const PADDING: f32 = 10.0;
 const SPACING: f32 = 10.0;
 const TEXT_SIZE: f32 = 18.0;
 const BUTTON_SIZE: f32 = 30.0;
-const CONTAINER_WIDTH: f32 = 420.0;
+const CONTAINER_WIDTH: f32 = 780.0;
+const COLOR_FIELD_HEIGHT: f32 = 160.0;
 
 impl Capture {
     pub fn view(&self) -> Element<'_, Message> {
@@ -83,7 +116,9 @@ impl Capture {
                 let description = match status {
                     CropState::FullScreen => String::from("Fullscreen"),
                     CropState::Window(window) => window.name.clone(),
-                    CropState::Area | CropState::InProgress { .. } => {
+                    CropState::Area
+                    | CropState::InProgress { .. }
+                    | CropState::Adjusting { .. } => {
                         format!("{} x {}", size.width as u32, size.height as u32)
                     }
                     CropState::None => "Exiting".to_string(),
@@ -94,24 +129,34 @@ impl Capture {
                 if status.is_idle() {
                     stack = stack.push(
                         self.toolbar(
-                            Row::new()
+                            Column::new()
+                                .push(self.tools_row())
+                                .extend(
+                                    matches!(status, CropState::Area).then(|| self.size_row(*size)),
+                                )
                                 .push(
-                                    Row::from_iter(Tool::ALL.into_iter().map(|tool| {
-                                        toolbar_icon(
-                                            tool.icon(),
-                                            TextClass::Default,
-                                            false,
-                                            Message::ChangeTool(tool),
+                                    Row::new()
+                                        .push(
+                                            PickList::new(
+                                                self.crop_presets.as_slice(),
+                                                Some(self.crop_preset),
+                                                Message::ChangeCropPreset,
+                                            )
+                                            .text_size(TEXT_SIZE)
+                                            .width(Length::Fill),
+                                        )
+                                        .push(
+                                            PickList::new(
+                                                &OutputFormat::ALL[..],
+                                                Some(&self.output_format),
+                                                Message::ChangeOutputFormat,
+                                            )
+                                            .text_size(TEXT_SIZE)
+                                            .width(Length::Fill),
                                         )
-                                    }))
-                                    .spacing(SPACING),
+                                        .spacing(SPACING),
                                 )
-                                .push(icon_button(
-                                    MOVE_ICON.to_string(),
-                                    TextClass::Default,
-                                    Message::MoveToolBar,
-                                    ButtonClass::Selected,
-                                ))
+                                .align_x(Alignment::Center)
                                 .spacing(SPACING),
                         ),
                     );
@@ -128,63 +173,64 @@ impl Capture {
 
                 if status.is_idle() || shape.tool.is_text_tool() {
                     let mut toolbar_column = Column::new()
-                        .push(
-                            Row::new()
-                                .push(
-                                    Row::from_iter(Tool::ALL.into_iter().map(|tool| {
-                                        toolbar_icon(
-                                            tool.icon(),
-                                            TextClass::Default,
-                                            shape.tool == tool,
-                                            Message::ChangeTool(tool),
-                                        )
-                                    }))
-                                    .spacing(SPACING),
-                                )
-                                .push(icon_button(
-                                    MOVE_ICON.to_string(),
-                                    TextClass::Default,
-                                    Message::MoveToolBar,
-                                    ButtonClass::Selected,
-                                ))
-                                .spacing(SPACING),
-                        )
-                        .push(
-                            Row::new()
-                                .push(
-                                    Slider::new(1..=5, shape.size, Message::ChangeSize)
-                                        .height(BUTTON_SIZE)
-                                        .width(Length::Fill),
-                                )
-                                .push(
-                                    Row::from_iter(ToolColor::ALL.into_iter().map(|color| {
-                                        toolbar_icon(
-                                            color.icon(),
-                                            TextClass::Custom(color.into()),
-                                            shape.color == color,
-                                            Message::ChangeColor(color),
-                                        )
-                                    }))
-                                    .spacing(SPACING),
-                                )
-                                .spacing(SPACING),
+                        .push(self.tools_row())
+                        .push(self.style_row(shape.size, shape.color))
+                        .extend(self.color_picker())
+                        .extend(
+                            shape
+                                .tool
+                                .is_text_tool()
+                                .then(|| text_style_row(&shape.text_style, &shape.tool)),
                         )
+                        .extend(line_style_row(&shape.line_style, &shape.tool))
                         .align_x(Alignment::Center)
                         .spacing(SPACING);
 
-                    if status.is_waiting_for_input()
-                        && let Tool::Text { text, .. } = &shape.tool
-                    {
+                    if status.is_waiting_for_input() {
                         toolbar_column = toolbar_column.push(
-                            TextInput::new("Enter Text", text)
-                                .width(Length::Fill)
-                                .font(MEDIUM_FONT)
+                            TextEditor::new(&self.text_content)
+                                .placeholder("Enter Text, Shift+Enter for a new line")
+                                .font(shape.text_style.font())
                                 .size(TEXT_SIZE)
-                                .on_input(Message::UpdateText)
+                                .on_action(Message::EditText)
+                                .key_binding(|key_press| match key_press.key {
+                                    Key::Named(Named::Enter) if !key_press.modifiers.shift() => {
+                                        Some(Binding::Custom(Message::CommitText))
+                                    }
+                                    _ => Binding::from_key_press(key_press),
+                                })
                                 .id("TextInput"),
                         );
                     }
 
+                    stack = stack.push(self.toolbar(toolbar_column))
+                };
+            }
+            Mode::Select { selected, state } => {
+                stack = stack.push(canvas_with_tooltip(format!(
+                    "{} x {}",
+                    self.cursor_position.x as u32, self.cursor_position.y as u32
+                )));
+
+                if state.is_idle() {
+                    let mut toolbar_column = Column::new()
+                        .push(self.tools_row())
+                        .align_x(Alignment::Center)
+                        .spacing(SPACING);
+
+                    if let Some(element) = selected.and_then(|index| self.elements.get(index)) {
+                        toolbar_column = toolbar_column
+                            .push(self.style_row(element.size, element.color))
+                            .extend(self.color_picker())
+                            .extend(
+                                element
+                                    .tool
+                                    .is_text_tool()
+                                    .then(|| text_style_row(&element.text_style, &element.tool)),
+                            )
+                            .extend(line_style_row(&element.line_style, &element.tool));
+                    }
+
                     stack = stack.push(self.toolbar(toolbar_column))
                 };
             }
@@ -193,6 +239,170 @@ impl Capture {
         stack.into()
     }
 
+    fn tools_row<'a>(&self) -> Element<'a, Message> {
+        let current_tool = match &self.mode {
+            Mode::Draw { element, .. } => Some(&element.tool),
+            _ => None,
+        };
+
+        Row::new()
+            .push(
+                Row::from_iter(Tool::ALL.into_iter().map(|tool| {
+                    let selected = current_tool == Some(&tool);
+
+                    toolbar_icon(
+                        tool.icon(),
+                        TextClass::Default,
+                        selected,
+                        Message::ChangeTool(tool),
+                    )
+                }))
+                .push(toolbar_icon(
+                    SELECT_ICON.to_string(),
+                    TextClass::Default,
+                    matches!(self.mode, Mode::Select { .. }),
+                    Message::Select,
+                ))
+                .spacing(SPACING),
+            )
+            .push(icon_button(
+                MOVE_ICON.to_string(),
+                TextClass::Default,
+                Message::MoveToolBar,
+                ButtonClass::Selected,
+            ))
+            .spacing(SPACING)
+            .into()
+    }
+
+    /// Exact size of the selected area, in physical pixels.
+    fn size_row<'a>(&self, size: Size) -> Element<'a, Message> {
+        let input = self
+            .size_input
+            .clone()
+            .unwrap_or_else(|| SizeInput::of(size, self.scale_factor));
+
+        Row::new()
+            .push(Text::new("Size").size(TEXT_SIZE))
+            .push(
+                TextInput::new("Width", &input.width)
+                    .font(MEDIUM_FONT)
+                    .size(TEXT_SIZE)
+                    .on_input(Message::ChangeCropWidth)
+                    .on_submit(Message::SubmitCropSize),
+            )
+            .push(Text::new("x").size(TEXT_SIZE))
+            .push(
+                TextInput::new("Height", &input.height)
+                    .font(MEDIUM_FONT)
+                    .size(TEXT_SIZE)
+                    .on_input(Message::ChangeCropHeight)
+                    .on_submit(Message::SubmitCropSize),
+            )
+            .align_y(Alignment::Center)
+            .spacing(SPACING)
+            .into()
+    }
+
+    fn style_row<'a>(&self, size: u32, color: ToolColor) -> Element<'a, Message> {
+        Row::new()
+            .push(
+                Slider::new(1..=5, size, Message::ChangeSize)
+                    .on_release(Message::EndDrag)
+                    .height(BUTTON_SIZE)
+                    .width(Length::Fill),
+            )
+            .push(
+                Row::from_iter(self.palette.iter().map(|&tool_color| {
+                    toolbar_icon(
+                        tool_color.icon(),
+                        TextClass::Custom(tool_color.into()),
+                        color == tool_color,
+                        Message::ChangeColor(tool_color),
+                    )
+                }))
+                .push(toolbar_icon(
+                    COLOR_PICKER_ICON.to_string(),
+                    TextClass::Custom(color.into()),
+                    self.color_picker.is_some(),
+                    Message::ToggleColorPicker,
+                ))
+                .push(toolbar_icon(
+                    EYEDROPPER_ICON.to_string(),
+                    TextClass::Default,
+                    self.eyedropper,
+                    Message::ToggleEyedropper,
+                ))
+                .spacing(SPACING),
+            )
+            .spacing(SPACING)
+            .into()
+    }
+
+    fn color_picker<'a>(&self) -> Option<Element<'a, Message>> {
+        let picker = self.color_picker.as_ref()?;
+
+        let is_favorite = self.palette.contains(&picker.color());
+
+        let slider_row = |label, slider: Element<'a, Message>| {
+            Row::new()
+                .push(Text::new(label).size(TEXT_SIZE).width(60))
+                .push(slider)
+                .align_y(Alignment::Center)
+                .spacing(SPACING)
+        };
+
+        Some(
+            Row::new()
+                .push(
+                    Canvas::new(HueSaturationField {
+                        hue: picker.hue,
+                        saturation: picker.saturation,
+                    })
+                    .width(Length::Fill)
+                    .height(COLOR_FIELD_HEIGHT),
+                )
+                .push(
+                    Column::new()
+                        .push(
+                            TextInput::new("#RRGGBBAA", &picker.hex)
+                                .font(MEDIUM_FONT)
+                                .size(TEXT_SIZE)
+                                .on_input(Message::UpdateHex),
+                        )
+                        .push(slider_row(
+                            "Value",
+                            Slider::new(0.0..=1.0, picker.value, Message::PickValue)
+                                .step(0.01)
+                                .on_release(Message::EndDrag)
+                                .into(),
+                        ))
+                        .push(slider_row(
+                            "Alpha",
+                            Slider::new(0..=255, picker.alpha, Message::PickAlpha)
+                                .on_release(Message::EndDrag)
+                                .into(),
+                        ))
+                        .push(
+                            Button::new(
+                                Text::new(match is_favorite {
+                                    true => "Remove from Favorites",
+                                    false => "Add to Favorites",
+                                })
+                                .size(TEXT_SIZE)
+                                .center(),
+                            )
+                            .on_press(Message::ToggleFavorite)
+                            .width(Length::Fill),
+                        )
+                        .spacing(SPACING)
+                        .width(Length::Fill),
+                )
+                .spacing(SPACING)
+                .into(),
+        )
+    }
+
     fn toolbar<'a>(&self, content: impl Into<Element<'a, Message>>) -> Element<'a, Message> {
         Container::new(opaque(
             Container::new(content)
@@ -211,6 +421,113 @@ impl Capture {
     }
 }
 
+fn text_style_row<'a>(text_style: &TextStyle, tool: &Tool) -> Element<'a, Message> {
+    let style_button = |label, font, selected, message| {
+        Button::new(Text::new(label).font(font).size(TEXT_SIZE).center())
+            .on_press(message)
+            .height(BUTTON_SIZE)
+            .width(BUTTON_SIZE)
+            .class(match selected {
+                true => ButtonClass::Selected,
+                false => ButtonClass::Default,
+            })
+    };
+
+    Row::new()
+        .push(style_button(
+            "B",
+            BOLD_FONT,
+            text_style.bold,
+            Message::ToggleBold,
+        ))
+        .push(style_button(
+            "I",
+            Font {
+                style: font::Style::Italic,
+                ..MEDIUM_FONT
+            },
+            text_style.italic,
+            Message::ToggleItalic,
+        ))
+        // Callouts always have their bubble
+        .extend(matches!(tool, Tool::Text { .. }).then(|| {
+            PickList::new(
+                &TextBackground::ALL[..],
+                Some(text_style.background),
+                Message::ChangeTextBackground,
+            )
+            .text_size(TEXT_SIZE)
+            .width(Length::FillPortion(1))
+            .into()
+        }))
+        .push(
+            PickList::new(
+                &FONT_FAMILIES[..],
+                Some(text_style.family().to_string()),
+                Message::ChangeFont,
+            )
+            .text_size(TEXT_SIZE)
+            .width(Length::FillPortion(2)),
+        )
+        .align_y(Alignment::Center)
+        .spacing(SPACING)
+        .into()
+}
+
+/// Stroke options of line-like tools, if the tool is one.
+fn line_style_row<'a>(line_style: &LineStyle, tool: &Tool) -> Option<Element<'a, Message>> {
+    let (arrow, line) = match tool {
+        Tool::Arrow { .. } => (true, true),
+        Tool::Line { .. } => (false, true),
+        Tool::FreeHand { .. } => (false, false),
+        Tool::Magnifier { .. } => {
+            return Some(toolbar_icon(
+                CONNECTOR_ICON.to_string(),
+                TextClass::Default,
+                line_style.connector,
+                Message::ToggleConnector,
+            ));
+        }
+        _ => return None,
+    };
+
+    let mut row = Row::new()
+        .push(
+            PickList::new(&Dash::ALL[..], Some(line_style.dash), Message::ChangeDash)
+                .text_size(TEXT_SIZE)
+                .width(Length::Fill),
+        )
+        .align_y(Alignment::Center)
+        .spacing(SPACING);
+
+    if arrow {
+        row = row
+            .push(toolbar_icon(
+                FILLED_HEAD_ICON.to_string(),
+                TextClass::Default,
+                line_style.filled_head,
+                Message::ToggleFilledHead,
+            ))
+            .push(toolbar_icon(
+                DOUBLE_HEAD_ICON.to_string(),
+                TextClass::Default,
+                line_style.double_head,
+                Message::ToggleDoubleHead,
+            ));
+    }
+
+    if line {
+        row = row.push(toolbar_icon(
+            CURVED_ICON.to_string(),
+            TextClass::Default,
+            line_style.curved,
+            Message::ToggleCurved,
+        ));
+    }
+
+    Some(row.into())
+}
+
 fn toolbar_icon<'a>(
     icon: String,
     text_class: TextClass,
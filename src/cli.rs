@@ -0,0 +1,313 @@
+use std::path::PathBuf;
+
+use anyhow::{
+    Context,
+    Error,
+    Result,
+    bail,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    Message,
+    ipc::protocol::Request,
+};
+
+pub const USAGE: &str = "\
+Usage: capter [COMMAND]
+
+Commands:
+  capture     Capture the screen
+  settings    Open the settings window
+  open <FILE> Open an image or a .capter project in the editor
+  quit        Exit the running instance
+
+Capture options:
+  --fullscreen          Capture the monitor under the cursor
+  --all-monitors        Capture every monitor into a single image
+  --each-monitor        Capture every monitor into a separate file
+  --window <TITLE>      Capture the first window whose title contains TITLE
+  --area <X,Y,W,H>      Capture an area of the desktop, in physical pixels
+  --last-region         Capture the area last selected in the capture window
+  --output <FILE>       Save the capture to FILE
+  --no-clipboard        Do not copy the capture to the clipboard
+  --delay <SECONDS>     Wait before capturing
+
+Without a capture target, the interactive capture window is opened.
+
+Options:
+  -h, --help            Print help
+  -V, --version         Print version";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Run,
+    Capture(CaptureArgs),
+    Settings,
+    Open(PathBuf),
+    Quit,
+    Help,
+    Version,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CaptureArgs {
+    pub target: Option<CaptureTarget>,
+    pub output: Option<PathBuf>,
+    pub copy_to_clipboard: bool,
+    #[serde(default)]
+    pub delay: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureTarget {
+    FullScreen,
+    AllMonitors,
+    EachMonitor,
+    Window(String),
+    WindowUnderCursor,
+    Area {
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+    },
+    LastRegion,
+}
+
+impl Command {
+    /// Parses the command line arguments, excluding the program name.
+    pub fn parse<I>(args: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut args = args.into_iter();
+
+        let command = match args.next().as_deref() {
+            None => Self::Run,
+            Some("capture") => Self::Capture(CaptureArgs::parse(&mut args)?),
+            Some("settings") => Self::Settings,
+            Some("open") => {
+                let path = args.next().context("Missing file to open")?;
+                Self::Open(std::path::absolute(path).context("Failed to resolve file path")?)
+            }
+            Some("quit") => Self::Quit,
+            Some("-h" | "--help" | "help") => Self::Help,
+            Some("-V" | "--version") => Self::Version,
+            Some(other) => bail!("Unknown command: {other}"),
+        };
+
+        if let Some(arg) = args.next() {
+            bail!("Unexpected argument: {arg}");
+        }
+
+        Ok(command)
+    }
+
+    /// Message sent to the daemon when it is started with this command.
+    pub fn startup_message(&self) -> Option<Message> {
+        match self {
+            Self::Capture(CaptureArgs {
+                target: None,
+                delay,
+                ..
+            }) => Some(Message::StartCountdown(
+                *delay,
+                Box::new(Message::OpenCaptureWindow),
+            )),
+            Self::Settings => Some(Message::OpenSettingsWindow),
+            Self::Open(path) => Some(Message::OpenFile(path.clone())),
+            _ => None,
+        }
+    }
+
+    /// Request forwarded to the running instance for this command.
+    pub fn request(&self) -> Option<Request> {
+        match self {
+            Self::Run | Self::Settings => Some(Request::OpenSettings),
+            Self::Capture(CaptureArgs {
+                target: None,
+                delay,
+                ..
+            }) => Some(Request::OpenCapture { delay: *delay }),
+            Self::Capture(args) => Some(Request::Capture(args.clone())),
+            Self::Open(path) => Some(Request::OpenFile { path: path.clone() }),
+            Self::Quit => Some(Request::Exit),
+            Self::Help | Self::Version => None,
+        }
+    }
+}
+
+impl CaptureArgs {
+    fn parse<I>(mut args: I) -> Result<Self>
+    where
+        I: Iterator<Item = String>,
+    {
+        let mut capture_args = Self {
+            target: None,
+            output: None,
+            copy_to_clipboard: true,
+            delay: 0,
+        };
+
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .with_context(|| format!("Missing value for {arg}"))
+            };
+
+            let target = match arg.as_str() {
+                "--fullscreen" => CaptureTarget::FullScreen,
+                "--all-monitors" => CaptureTarget::AllMonitors,
+                "--each-monitor" => CaptureTarget::EachMonitor,
+                "--window" => CaptureTarget::Window(value()?),
+                "--area" => CaptureTarget::parse_area(&value()?)?,
+                "--last-region" => CaptureTarget::LastRegion,
+                "--output" => {
+                    let path =
+                        std::path::absolute(value()?).context("Failed to resolve output path")?;
+                    capture_args.output = Some(path);
+                    continue;
+                }
+                "--no-clipboard" => {
+                    capture_args.copy_to_clipboard = false;
+                    continue;
+                }
+                "--delay" => {
+                    let value = value()?;
+                    capture_args.delay = value
+                        .parse()
+                        .with_context(|| format!("Invalid delay: {value}"))?;
+                    continue;
+                }
+                _ => bail!("Unknown capture option: {arg}"),
+            };
+
+            if capture_args.target.replace(target).is_some() {
+                bail!("Only one capture target can be used");
+            }
+        }
+
+        if capture_args.target.is_none()
+            && (capture_args.output.is_some() || !capture_args.copy_to_clipboard)
+        {
+            bail!("--output and --no-clipboard require a capture target");
+        }
+
+        Ok(capture_args)
+    }
+}
+
+impl CaptureTarget {
+    fn parse_area(value: &str) -> Result<Self> {
+        let invalid = || Error::msg(format!("Invalid area: {value}, expected X,Y,W,H"));
+
+        let parts = value
+            .split(',')
+            .map(|part| part.trim().parse::<i64>().map_err(|_| invalid()))
+            .collect::<Result<Vec<_>>>()?;
+
+        let [x, y, width, height] = parts[..] else {
+            return Err(invalid());
+        };
+
+        if width <= 0 || height <= 0 {
+            bail!("Area width and height must be positive");
+        }
+
+        Ok(Self::Area {
+            x: i32::try_from(x).map_err(|_| invalid())?,
+            y: i32::try_from(y).map_err(|_| invalid())?,
+            width: u32::try_from(width).map_err(|_| invalid())?,
+            height: u32::try_from(height).map_err(|_| invalid())?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command> {
+        Command::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    fn capture(args: &[&str]) -> CaptureArgs {
+        match parse(args) {
+            Ok(Command::Capture(args)) => args,
+            other => panic!("Expected a capture command, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parses_commands() {
+        assert_eq!(parse(&[]).unwrap(), Command::Run);
+        assert_eq!(parse(&["settings"]).unwrap(), Command::Settings);
+        assert_eq!(parse(&["quit"]).unwrap(), Command::Quit);
+        assert_eq!(parse(&["--help"]).unwrap(), Command::Help);
+        assert_eq!(parse(&["-V"]).unwrap(), Command::Version);
+        assert!(parse(&["unknown"]).is_err());
+        assert!(parse(&["settings", "extra"]).is_err());
+        assert!(parse(&["open"]).is_err());
+    }
+
+    #[test]
+    fn parses_capture_targets() {
+        assert_eq!(capture(&["capture"]).target, None);
+        assert_eq!(
+            capture(&["capture", "--fullscreen"]).target,
+            Some(CaptureTarget::FullScreen)
+        );
+        assert_eq!(
+            capture(&["capture", "--each-monitor"]).target,
+            Some(CaptureTarget::EachMonitor)
+        );
+        assert_eq!(
+            capture(&["capture", "--window", "Firefox"]).target,
+            Some(CaptureTarget::Window("Firefox".to_string()))
+        );
+        assert_eq!(
+            capture(&["capture", "--last-region"]).target,
+            Some(CaptureTarget::LastRegion)
+        );
+        assert!(parse(&["capture", "--fullscreen", "--all-monitors"]).is_err());
+        assert!(parse(&["capture", "--window"]).is_err());
+    }
+
+    #[test]
+    fn parses_capture_options() {
+        let args = capture(&["capture", "--fullscreen", "--no-clipboard", "--delay", "3"]);
+
+        assert!(!args.copy_to_clipboard);
+        assert_eq!(args.delay, 3);
+        assert!(
+            capture(&["capture", "--output", "shot.png", "--fullscreen"])
+                .output
+                .is_some_and(|path| path.is_absolute() && path.ends_with("shot.png"))
+        );
+        assert!(parse(&["capture", "--delay", "soon"]).is_err());
+        assert!(parse(&["capture", "--no-clipboard"]).is_err());
+        assert!(parse(&["capture", "--output", "shot.png"]).is_err());
+    }
+
+    #[test]
+    fn parses_areas() {
+        assert_eq!(
+            capture(&["capture", "--area", "-10, 20,1280,720"]).target,
+            Some(CaptureTarget::Area {
+                x: -10,
+                y: 20,
+                width: 1280,
+                height: 720,
+            })
+        );
+        assert!(parse(&["capture", "--area", "0,0,1280"]).is_err());
+        assert!(parse(&["capture", "--area", "0,0,0,720"]).is_err());
+        assert!(parse(&["capture", "--area", "0,0,-5,720"]).is_err());
+        assert!(parse(&["capture", "--area", "a,b,c,d"]).is_err());
+    }
+}
@@ -20,25 +20,68 @@ use serde::{
 };
 
 use crate::{
+    capture::{
+        CropPreset,
+        ToolColor,
+    },
+    file_name::DEFAULT_TEMPLATE,
+    headless::Region,
+    hotkeys::Hotkeys,
     organize_type::OrgranizeMode,
+    output_format::OutputFormat,
     theme::Theme,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub folder_path: PathBuf,
-    pub organize_mode: OrgranizeMode,
+    #[serde(default = "Config::default_file_name_template")]
+    pub file_name_template: String,
+    /// Only read to migrate configs from before file name templates.
+    #[serde(default, skip_serializing)]
+    organize_mode: Option<OrgranizeMode>,
     pub show_notification: bool,
     pub theme: Theme,
+    #[serde(default = "Config::default_capture_delay")]
+    pub capture_delay: u32,
+    #[serde(default)]
+    pub span_all_monitors: bool,
+    #[serde(default)]
+    pub output_format: OutputFormat,
+    #[serde(default = "Config::default_image_quality")]
+    pub image_quality: u8,
+    #[serde(default)]
+    pub export_svg: bool,
+    #[serde(default)]
+    pub save_project: bool,
+    #[serde(default = "Config::default_palette")]
+    pub palette: Vec<ToolColor>,
+    #[serde(default = "Config::default_crop_presets")]
+    pub crop_presets: Vec<CropPreset>,
+    #[serde(default)]
+    pub hotkeys: Hotkeys,
+    #[serde(default)]
+    pub last_region: Option<Region>,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             folder_path: Self::default_screenshot_dir(),
-            organize_mode: Default::default(),
+            file_name_template: Self::default_file_name_template(),
+            organize_mode: None,
             show_notification: true,
             theme: Default::default(),
+            capture_delay: Self::default_capture_delay(),
+            span_all_monitors: false,
+            output_format: Default::default(),
+            image_quality: Self::default_image_quality(),
+            export_svg: false,
+            save_project: false,
+            palette: Self::default_palette(),
+            crop_presets: Self::default_crop_presets(),
+            hotkeys: Default::default(),
+            last_region: None,
         }
     }
 }
@@ -50,7 +93,7 @@ impl Config {
         let mut file_content = String::new();
 
         let _ = config_file.read_to_string(&mut file_content);
-        let config = toml::from_str::<Config>(&file_content).unwrap_or_else(|_| {
+        let mut config = toml::from_str::<Config>(&file_content).unwrap_or_else(|_| {
             let default_config = Self::default();
             default_config
                 .save()
@@ -58,6 +101,11 @@ impl Config {
             default_config
         });
 
+        if let Some(organize_mode) = config.organize_mode.take() {
+            config.file_name_template = organize_mode.template().to_string();
+            config.save()?;
+        }
+
         Ok((config, is_newly_created))
     }
 
@@ -70,6 +118,20 @@ impl Config {
         Ok(())
     }
 
+    /// Sets a config key from a JSON value, using `.` to separate nested keys.
+    pub fn set(&mut self, key: &str, value: serde_json::Value) -> Result<()> {
+        let mut config = serde_json::to_value(&*self)?;
+
+        *config
+            .pointer_mut(&format!("/{}", key.replace('.', "/")))
+            .with_context(|| format!("Unknown config key: {key}"))? = value;
+
+        *self = serde_json::from_value(config)
+            .with_context(|| format!("Invalid value for config key: {key}"))?;
+
+        self.save()
+    }
+
     /// Gets the config file path and ensures the folder exists.
     fn get_config_file() -> Result<(File, bool)> {
         let config_folder = dirs::config_dir()
@@ -109,6 +171,26 @@ impl Config {
         }
     }
 
+    fn default_file_name_template() -> String {
+        DEFAULT_TEMPLATE.to_string()
+    }
+
+    fn default_capture_delay() -> u32 {
+        5
+    }
+
+    fn default_image_quality() -> u8 {
+        90
+    }
+
+    fn default_palette() -> Vec<ToolColor> {
+        ToolColor::DEFAULT_PALETTE.to_vec()
+    }
+
+    fn default_crop_presets() -> Vec<CropPreset> {
+        CropPreset::DEFAULTS.to_vec()
+    }
+
     /// Provides the default screenshots folder.
     fn default_screenshot_dir() -> PathBuf {
         let screenshot_dir = dirs::picture_dir()
@@ -51,4 +51,34 @@ pub const HIGHLIGHTER_ICON: char = '\u{F108}';
 
 pub const TEXT_ICON: char = '\u{F109}';
 
+pub const SELECT_ICON: char = '\u{F10A}';
+
+pub const PIXELATE_ICON: char = '\u{F10B}';
+
+pub const BLUR_ICON: char = '\u{F10C}';
+
+pub const SPOTLIGHT_ICON: char = '\u{F10D}';
+
+pub const ROUND_SPOTLIGHT_ICON: char = '\u{F10E}';
+
+pub const COUNTER_ICON: char = '\u{F10F}';
+
+pub const CALLOUT_ICON: char = '\u{F110}';
+
+pub const MAGNIFIER_ICON: char = '\u{F111}';
+
+pub const SQUARE_MAGNIFIER_ICON: char = '\u{F112}';
+
 pub const MOVE_ICON: char = '\u{F201}';
+
+pub const COLOR_PICKER_ICON: char = '\u{F202}';
+
+pub const EYEDROPPER_ICON: char = '\u{F203}';
+
+pub const FILLED_HEAD_ICON: char = '\u{F204}';
+
+pub const DOUBLE_HEAD_ICON: char = '\u{F205}';
+
+pub const CURVED_ICON: char = '\u{F206}';
+
+pub const CONNECTOR_ICON: char = '\u{F207}';
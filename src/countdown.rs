@@ -0,0 +1,62 @@
+use iced::{
+    Length,
+    widget::{
+        Container,
+        Text,
+    },
+    window::Id,
+};
+
+use crate::{
+    Message,
+    consts::BOLD_FONT,
+    theme::Element,
+};
+
+/// Seconds left before a delayed capture, shown on the tray icon and in a corner overlay
+/// where the overlay can't take focus.
+pub struct Countdown {
+    remaining: u32,
+    then: Option<Message>,
+    pub overlay: Option<Id>,
+}
+
+impl Countdown {
+    pub fn new(seconds: u32, then: Message, overlay: Option<Id>) -> Self {
+        Self {
+            remaining: seconds,
+            then: Some(then),
+            overlay,
+        }
+    }
+
+    pub fn status(&self) -> String {
+        format!("Capturing in {}s", self.remaining)
+    }
+
+    /// Counts down a second and returns the delayed message once time is up.
+    pub fn tick(&mut self) -> Option<Message> {
+        self.remaining = self.remaining.saturating_sub(1);
+
+        if self.remaining == 0 {
+            self.then.take()
+        } else {
+            None
+        }
+    }
+
+    /// Takes back the delayed message, so it is never sent.
+    pub fn cancel(&mut self) -> Option<Message> {
+        self.then.take()
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        Container::new(
+            Text::new(self.remaining.to_string())
+                .size(36)
+                .font(BOLD_FONT),
+        )
+        .center(Length::Fill)
+        .into()
+    }
+}
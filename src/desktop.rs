@@ -0,0 +1,114 @@
+use anyhow::{
+    Context,
+    Result,
+    bail,
+};
+use xcap::{
+    Monitor,
+    image::{
+        RgbaImage,
+        imageops::{
+            FilterType,
+            overlay,
+            resize,
+        },
+    },
+};
+
+/// Screenshot of every monitor stitched into one image of the virtual desktop.
+pub struct Desktop {
+    pub screenshot: RgbaImage,
+    /// Top left corner, in the coordinates reported by xcap.
+    pub origin: (i32, i32),
+    /// Screenshot pixels per xcap coordinate unit.
+    pub density: f32,
+}
+
+impl Desktop {
+    pub fn capture() -> Result<Self> {
+        let monitors = Monitor::all()
+            .with_context(|| "Unable to list Monitors")?
+            .into_iter()
+            .map(|monitor| {
+                let screenshot = monitor
+                    .capture_image()
+                    .with_context(|| "Unable to capture Monitor")?;
+                Ok((
+                    monitor.x()?,
+                    monitor.y()?,
+                    monitor.width()?,
+                    monitor.height()?,
+                    screenshot,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if monitors.is_empty() {
+            bail!("No Monitor found");
+        }
+
+        // Monitors may report logical coordinates, so the densest one decides
+        // the resolution of the whole desktop.
+        let density = monitors
+            .iter()
+            .map(|(_, _, width, _, screenshot)| screenshot.width() as f32 / *width as f32)
+            .fold(1.0, f32::max);
+
+        let left = monitors.iter().map(|(x, ..)| *x).min().unwrap_or_default();
+        let top = monitors
+            .iter()
+            .map(|(_, y, ..)| *y)
+            .min()
+            .unwrap_or_default();
+        let right = monitors
+            .iter()
+            .map(|(x, _, width, ..)| *x + *width as i32)
+            .max()
+            .unwrap_or_default();
+        let bottom = monitors
+            .iter()
+            .map(|(_, y, _, height, _)| *y + *height as i32)
+            .max()
+            .unwrap_or_default();
+
+        let mut desktop = Self {
+            screenshot: RgbaImage::new(
+                ((right - left) as f32 * density) as u32,
+                ((bottom - top) as f32 * density) as u32,
+            ),
+            origin: (left, top),
+            density,
+        };
+
+        for (x, y, width, height, screenshot) in monitors {
+            let (pixel_x, pixel_y) = desktop.to_pixels(x, y);
+            let (pixel_width, pixel_height) = (
+                (width as f32 * density) as u32,
+                (height as f32 * density) as u32,
+            );
+
+            let screenshot = if screenshot.dimensions() == (pixel_width, pixel_height) {
+                screenshot
+            } else {
+                resize(&screenshot, pixel_width, pixel_height, FilterType::Triangle)
+            };
+
+            overlay(
+                &mut desktop.screenshot,
+                &screenshot,
+                pixel_x as i64,
+                pixel_y as i64,
+            );
+        }
+
+        Ok(desktop)
+    }
+
+    /// Converts xcap coordinates to a position on the desktop screenshot.
+    pub fn to_pixels(&self, x: i32, y: i32) -> (f32, f32) {
+        (
+            (x - self.origin.0) as f32 * self.density,
+            (y - self.origin.1) as f32 * self.density,
+        )
+    }
+}
@@ -0,0 +1,321 @@
+use std::path::{
+    Path,
+    PathBuf,
+};
+
+use chrono::{
+    DateTime,
+    Local,
+    format::{
+        Item,
+        StrftimeItems,
+    },
+};
+
+use crate::consts::APPNAME;
+
+pub const DEFAULT_TEMPLATE: &str = "{app}_{date}.{ext}";
+
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d_%H-%M-%S";
+
+/// Longest `{counter:DIGITS}` padding, enough for any counter.
+const MAX_COUNTER_DIGITS: usize = 10;
+
+const ILLEGAL_CHARS: [char; 9] = ['/', '\\', '<', '>', ':', '"', '|', '?', '*'];
+
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureKind {
+    Fullscreen,
+    Window,
+    Area,
+}
+
+impl std::fmt::Display for CaptureKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CaptureKind::Fullscreen => write!(f, "fullscreen"),
+            CaptureKind::Window => write!(f, "window"),
+            CaptureKind::Area => write!(f, "area"),
+        }
+    }
+}
+
+/// Details about a capture, available as placeholders in file name templates.
+#[derive(Debug, Clone)]
+pub struct CaptureInfo {
+    pub kind: CaptureKind,
+    pub window_title: Option<String>,
+    pub monitor_name: Option<String>,
+}
+
+impl CaptureInfo {
+    pub fn new(kind: CaptureKind) -> Self {
+        Self {
+            kind,
+            window_title: None,
+            monitor_name: None,
+        }
+    }
+}
+
+/// Resolves the template to a file in `folder`, picking the first free `{counter}`.
+pub fn image_path(
+    folder: &Path,
+    template: &str,
+    info: &CaptureInfo,
+    (width, height): (u32, u32),
+    extension: &str,
+) -> PathBuf {
+    let now = Local::now();
+
+    let path = |counter| {
+        folder.join(render(
+            template,
+            info,
+            (width, height),
+            extension,
+            counter,
+            now,
+        ))
+    };
+
+    if !template.contains("{counter") {
+        return path(1);
+    }
+
+    (1..)
+        .map(path)
+        .find(|path| !path.exists())
+        .expect("Counter must find a free path")
+}
+
+/// Renders the template to a sanitized path, relative to the screenshots folder.
+///
+/// Supported placeholders are `{app}`, `{date}`, `{date:FORMAT}`, `{window_title}`,
+/// `{monitor}`, `{kind}`, `{w}`, `{h}`, `{counter}`, `{counter:DIGITS}` and `{ext}`.
+/// Unknown placeholders are kept as is.
+pub fn render(
+    template: &str,
+    info: &CaptureInfo,
+    (width, height): (u32, u32),
+    extension: &str,
+    counter: u32,
+    now: DateTime<Local>,
+) -> PathBuf {
+    let mut rendered = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let Some(end) = rest.find('}') else {
+            break;
+        };
+
+        let placeholder = &rest[1..end];
+        let (name, argument) = match placeholder.split_once(':') {
+            Some((name, argument)) => (name, Some(argument)),
+            None => (placeholder, None),
+        };
+
+        let value = match (name, argument) {
+            ("app", None) => Some(sanitize(APPNAME)),
+            // Separators in the date format create folders
+            ("date", format) => {
+                let format = format.unwrap_or(DEFAULT_DATE_FORMAT);
+
+                (!StrftimeItems::new(format).any(|item| matches!(item, Item::Error)))
+                    .then(|| now.format(format).to_string())
+            }
+            ("window_title", None) => {
+                Some(sanitize(info.window_title.as_deref().unwrap_or_default()))
+            }
+            ("monitor", None) => Some(sanitize(info.monitor_name.as_deref().unwrap_or_default())),
+            ("kind", None) => Some(info.kind.to_string()),
+            ("w", None) => Some(width.to_string()),
+            ("h", None) => Some(height.to_string()),
+            ("counter", None) => Some(counter.to_string()),
+            ("counter", Some(digits)) => digits.parse::<usize>().ok().map(|digits| {
+                let digits = digits.min(MAX_COUNTER_DIGITS);
+                format!("{counter:0digits$}")
+            }),
+            ("ext", None) => Some(extension.to_string()),
+            _ => None,
+        };
+
+        match value {
+            Some(value) => rendered.push_str(&value),
+            None => rendered.push_str(&rest[..=end]),
+        }
+
+        rest = &rest[end + 1..];
+    }
+    rendered.push_str(rest);
+
+    if !template.contains("{ext}") {
+        rendered.push('.');
+        rendered.push_str(extension);
+    }
+
+    let path = rendered
+        .split(['/', '\\'])
+        .map(sanitize_component)
+        .filter(|component| !component.is_empty())
+        .collect::<PathBuf>();
+
+    if path
+        .file_name()
+        .is_none_or(|name| name.to_string_lossy().starts_with('.'))
+    {
+        return render(
+            DEFAULT_TEMPLATE,
+            info,
+            (width, height),
+            extension,
+            counter,
+            now,
+        );
+    }
+
+    path
+}
+
+/// Replaces characters which are not allowed in a file name.
+fn sanitize(value: &str) -> String {
+    value
+        .chars()
+        .map(
+            |char| match ILLEGAL_CHARS.contains(&char) || char.is_control() {
+                true => '_',
+                false => char,
+            },
+        )
+        .collect()
+}
+
+fn sanitize_component(component: &str) -> String {
+    let component = sanitize(component);
+    let component = component.trim().trim_end_matches('.');
+
+    let stem = component.split('.').next().unwrap_or_default();
+
+    match RESERVED_NAMES.contains(&stem.to_uppercase().as_str()) {
+        true => format!("_{component}"),
+        false => component.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn render_with(template: &str, info: &CaptureInfo, counter: u32) -> PathBuf {
+        let now = Local.with_ymd_and_hms(2024, 3, 5, 14, 7, 9).unwrap();
+
+        render(template, info, (1920, 1080), "png", counter, now)
+    }
+
+    fn rendered(template: &str) -> PathBuf {
+        render_with(template, &CaptureInfo::new(CaptureKind::Area), 1)
+    }
+
+    #[test]
+    fn renders_placeholders() {
+        assert_eq!(
+            rendered(DEFAULT_TEMPLATE),
+            PathBuf::from(format!("{APPNAME}_2024-03-05_14-07-09.png"))
+        );
+        assert_eq!(
+            rendered("{kind}_{w}x{h}_{date:%H%M}.{ext}"),
+            PathBuf::from("area_1920x1080_1407.png")
+        );
+
+        let mut info = CaptureInfo::new(CaptureKind::Window);
+        info.window_title = Some(String::from("Docs: a/b"));
+        info.monitor_name = Some(String::from("DP-1"));
+        assert_eq!(
+            render_with("{monitor}_{window_title}.{ext}", &info, 1),
+            PathBuf::from("DP-1_Docs_ a_b.png")
+        );
+    }
+
+    #[test]
+    fn creates_folders_from_dates() {
+        assert_eq!(
+            rendered("{date:%Y/%m}/{kind}.{ext}"),
+            ["2024", "03", "area.png"].iter().collect::<PathBuf>()
+        );
+    }
+
+    #[test]
+    fn pads_counters() {
+        let info = CaptureInfo::new(CaptureKind::Area);
+
+        assert_eq!(
+            render_with("{counter}.{ext}", &info, 7),
+            PathBuf::from("7.png")
+        );
+        assert_eq!(
+            render_with("{counter:3}.{ext}", &info, 7),
+            PathBuf::from("007.png")
+        );
+        assert_eq!(
+            render_with("{counter:999999999}.{ext}", &info, 7),
+            PathBuf::from("0000000007.png")
+        );
+    }
+
+    #[test]
+    fn keeps_unknown_placeholders() {
+        assert_eq!(
+            rendered("{unknown}_{w}.{ext}"),
+            PathBuf::from("{unknown}_1920.png")
+        );
+        assert_eq!(rendered("{date:%Q}.{ext}"), PathBuf::from("{date_%Q}.png"));
+    }
+
+    #[test]
+    fn appends_missing_extension() {
+        assert_eq!(rendered("{kind}"), PathBuf::from("area.png"));
+    }
+
+    #[test]
+    fn sanitizes_paths() {
+        assert_eq!(
+            rendered("../{kind}/./CON.{ext}"),
+            ["area", "_CON.png"].iter().collect::<PathBuf>()
+        );
+        assert_eq!(
+            rendered("//{kind}//{w}.{ext}"),
+            ["area", "1920.png"].iter().collect::<PathBuf>()
+        );
+    }
+
+    #[test]
+    fn falls_back_to_default_template() {
+        let default = rendered(DEFAULT_TEMPLATE);
+
+        assert_eq!(rendered(""), default);
+        assert_eq!(rendered(".{ext}"), default);
+        assert_eq!(rendered("{kind}/"), default);
+    }
+
+    #[test]
+    fn sanitizes_components() {
+        assert_eq!(sanitize_component("a:b*c"), "a_b_c");
+        assert_eq!(sanitize_component(" name. "), "name");
+        assert_eq!(sanitize_component(".."), "");
+        assert_eq!(sanitize_component("con"), "_con");
+        assert_eq!(sanitize_component("LPT1.txt"), "_LPT1.txt");
+        assert_eq!(sanitize_component("CONSOLE"), "CONSOLE");
+        assert_eq!(sanitize_component("a\\b"), "a_b");
+    }
+}
@@ -0,0 +1,336 @@
+use std::{
+    collections::HashSet,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+use anyhow::{
+    Context,
+    Result,
+    bail,
+};
+use mouse_position::mouse_position::Mouse;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use xcap::{
+    Monitor,
+    Window,
+    image::{
+        RgbaImage,
+        imageops::crop_imm,
+    },
+};
+
+use crate::{
+    capture::image::save_image,
+    cli::{
+        CaptureArgs,
+        CaptureTarget,
+    },
+    config::Config,
+    desktop::Desktop,
+    file_name::{
+        CaptureInfo,
+        CaptureKind,
+        image_path,
+    },
+    output_format::OutputFormat,
+};
+
+/// Area of a monitor last captured from the capture window.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Region {
+    /// Id given by the system, which changes across restarts on Windows.
+    pub monitor_id: u32,
+    /// Name, position on the desktop and size of the monitor, to find it again by.
+    #[serde(default)]
+    pub monitor_name: String,
+    #[serde(default)]
+    pub monitor_position: [i32; 2],
+    #[serde(default)]
+    pub monitor_size: [u32; 2],
+    /// Corners in physical pixels, relative to the monitor.
+    pub top_left: [u32; 2],
+    pub bottom_right: [u32; 2],
+}
+
+impl Region {
+    pub fn new(monitor: &Monitor, top_left: [u32; 2], bottom_right: [u32; 2]) -> Option<Self> {
+        Some(Self {
+            monitor_id: monitor.id().ok()?,
+            monitor_name: monitor.name().ok()?,
+            monitor_position: [monitor.x().ok()?, monitor.y().ok()?],
+            monitor_size: [monitor.width().ok()?, monitor.height().ok()?],
+            top_left,
+            bottom_right,
+        })
+    }
+
+    /// Monitor the region was captured on, matched by name, position and size,
+    /// or by id when those changed.
+    fn monitor(&self) -> Result<Monitor> {
+        let mut monitors = Monitor::all().with_context(|| "Unable to list Monitors")?;
+
+        let is_same = |monitor: &Monitor| {
+            Region::new(monitor, self.top_left, self.bottom_right).is_some_and(|region| {
+                region.monitor_name == self.monitor_name
+                    && region.monitor_position == self.monitor_position
+                    && region.monitor_size == self.monitor_size
+            })
+        };
+
+        let index = monitors
+            .iter()
+            .position(is_same)
+            .or_else(|| {
+                monitors
+                    .iter()
+                    .position(|monitor| monitor.id().ok() == Some(self.monitor_id))
+            })
+            .with_context(|| "Monitor of the last region is not connected")?;
+
+        Ok(monitors.swap_remove(index))
+    }
+}
+
+/// Captures the requested target without opening the capture window.
+pub fn capture(args: &CaptureArgs, config: &Config) -> Result<Vec<PathBuf>> {
+    let (image, info) = match &args.target {
+        Some(CaptureTarget::EachMonitor) => return capture_each_monitor(args, config),
+        Some(CaptureTarget::FullScreen) | None => capture_fullscreen()?,
+        Some(CaptureTarget::AllMonitors) => (
+            Desktop::capture()?.screenshot,
+            CaptureInfo::new(CaptureKind::Fullscreen),
+        ),
+        Some(CaptureTarget::Window(title)) => capture_window(title)?,
+        Some(CaptureTarget::WindowUnderCursor) => capture_window_under_cursor()?,
+        Some(CaptureTarget::Area {
+            x,
+            y,
+            width,
+            height,
+        }) => capture_area(*x, *y, *width, *height)?,
+        Some(CaptureTarget::LastRegion) => capture_last_region(config)?,
+    };
+
+    save_image(
+        image,
+        config,
+        args.output.clone(),
+        None,
+        &info,
+        args.copy_to_clipboard,
+    )
+    .map(|path| vec![path])
+}
+
+/// Saves every monitor to its own file, numbered if their paths would collide.
+fn capture_each_monitor(args: &CaptureArgs, config: &Config) -> Result<Vec<PathBuf>> {
+    let captures = Monitor::all()
+        .with_context(|| "Unable to list Monitors")?
+        .iter()
+        .map(capture_monitor)
+        .collect::<Result<Vec<_>>>()?;
+
+    if captures.is_empty() {
+        bail!("No Monitor found");
+    }
+
+    let format = args
+        .output
+        .as_deref()
+        .and_then(OutputFormat::from_path)
+        .unwrap_or(config.output_format);
+
+    let paths = captures
+        .iter()
+        .map(|(image, info)| match &args.output {
+            Some(output) => output.clone(),
+            None => image_path(
+                &config.folder_path,
+                &config.file_name_template,
+                info,
+                image.dimensions(),
+                format.extension(),
+            ),
+        })
+        .collect::<Vec<_>>();
+
+    let is_unique = paths.iter().collect::<HashSet<_>>().len() == paths.len();
+
+    captures
+        .into_iter()
+        .zip(paths)
+        .enumerate()
+        .map(|(index, ((image, info), path))| {
+            let path = match is_unique {
+                true => path,
+                false => numbered_path(&path, index + 1),
+            };
+
+            // Only one image fits in the clipboard, so none is copied.
+            save_image(image, config, Some(path), Some(format), &info, false)
+        })
+        .collect()
+}
+
+fn numbered_path(path: &Path, number: usize) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy())
+        .unwrap_or_default();
+
+    let file_name = match path.extension() {
+        Some(extension) => format!("{stem}_{number}.{}", extension.to_string_lossy()),
+        None => format!("{stem}_{number}"),
+    };
+
+    path.with_file_name(file_name)
+}
+
+fn capture_fullscreen() -> Result<(RgbaImage, CaptureInfo)> {
+    let (x, y) = mouse_position();
+
+    let monitor = Monitor::from_point(x, y).with_context(|| "Unable to find Monitor")?;
+
+    capture_monitor(&monitor)
+}
+
+fn capture_monitor(monitor: &Monitor) -> Result<(RgbaImage, CaptureInfo)> {
+    let image = monitor
+        .capture_image()
+        .with_context(|| "Unable to capture Monitor")?;
+
+    let mut info = CaptureInfo::new(CaptureKind::Fullscreen);
+    info.monitor_name = monitor.name().ok();
+
+    Ok((image, info))
+}
+
+fn capture_window(title: &str) -> Result<(RgbaImage, CaptureInfo)> {
+    let windows = Window::all().with_context(|| "Unable to list windows")?;
+
+    let window = windows
+        .iter()
+        .find(|window| window.title().is_ok_and(|name| name == title))
+        .or_else(|| {
+            windows
+                .iter()
+                .find(|window| window.title().is_ok_and(|name| name.contains(title)))
+        })
+        .with_context(|| format!("No window found with title: {title}"))?;
+
+    if window.is_minimized().unwrap_or_default() {
+        bail!("Window is minimized: {title}");
+    }
+
+    capture_window_image(window)
+}
+
+fn capture_window_under_cursor() -> Result<(RgbaImage, CaptureInfo)> {
+    let (x, y) = mouse_position();
+
+    let window = Window::all()
+        .with_context(|| "Unable to list windows")?
+        .into_iter()
+        .find(|window| is_below_point(window, x, y).unwrap_or_default())
+        .with_context(|| "No window found under the cursor")?;
+
+    capture_window_image(&window)
+}
+
+fn capture_window_image(window: &Window) -> Result<(RgbaImage, CaptureInfo)> {
+    let image = window
+        .capture_image()
+        .with_context(|| "Unable to capture Window")?;
+
+    let mut info = CaptureInfo::new(CaptureKind::Window);
+    info.window_title = window.title().ok();
+    info.monitor_name = window
+        .current_monitor()
+        .and_then(|monitor| monitor.name())
+        .ok();
+
+    Ok((image, info))
+}
+
+fn is_below_point(window: &Window, x: i32, y: i32) -> Option<bool> {
+    let (left, top) = (window.x().ok()?, window.y().ok()?);
+    let (width, height) = (window.width().ok()? as i32, window.height().ok()? as i32);
+
+    Some(
+        !window.is_minimized().ok()?
+            && !window.title().ok()?.is_empty()
+            && (left..left + width).contains(&x)
+            && (top..top + height).contains(&y),
+    )
+}
+
+fn capture_area(x: i32, y: i32, width: u32, height: u32) -> Result<(RgbaImage, CaptureInfo)> {
+    let monitor = Monitor::from_point(x, y).with_context(|| "No Monitor found at the area")?;
+
+    let (monitor_x, monitor_y) = (
+        monitor
+            .x()
+            .with_context(|| "Unable to get Monitor position")?,
+        monitor
+            .y()
+            .with_context(|| "Unable to get Monitor position")?,
+    );
+
+    let screenshot = monitor
+        .capture_image()
+        .with_context(|| "Unable to capture Monitor")?;
+
+    let (x, y) = ((x - monitor_x) as u32, (y - monitor_y) as u32);
+
+    if x.saturating_add(width) > screenshot.width()
+        || y.saturating_add(height) > screenshot.height()
+    {
+        bail!("Area must fit within a single Monitor");
+    }
+
+    let mut info = CaptureInfo::new(CaptureKind::Area);
+    info.monitor_name = monitor.name().ok();
+
+    Ok((crop_imm(&screenshot, x, y, width, height).to_image(), info))
+}
+
+fn capture_last_region(config: &Config) -> Result<(RgbaImage, CaptureInfo)> {
+    let region = config
+        .last_region
+        .as_ref()
+        .with_context(|| "No previous capture region to repeat")?;
+    let ([left, top], [right, bottom]) = (region.top_left, region.bottom_right);
+
+    let monitor = region.monitor()?;
+
+    let screenshot = monitor
+        .capture_image()
+        .with_context(|| "Unable to capture Monitor")?;
+
+    // The monitor may have changed resolution since
+    if left >= right || top >= bottom || right > screenshot.width() || bottom > screenshot.height()
+    {
+        bail!("Last region no longer fits its Monitor");
+    }
+
+    let mut info = CaptureInfo::new(CaptureKind::Area);
+    info.monitor_name = monitor.name().ok();
+
+    Ok((
+        crop_imm(&screenshot, left, top, right - left, bottom - top).to_image(),
+        info,
+    ))
+}
+
+fn mouse_position() -> (i32, i32) {
+    match Mouse::get_mouse_position() {
+        Mouse::Position { x, y } => (x, y),
+        Mouse::Error => (0, 0),
+    }
+}
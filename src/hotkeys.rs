@@ -0,0 +1,429 @@
+use std::fmt::Display;
+
+use anyhow::{
+    Error,
+    Result,
+    bail,
+};
+use iced::keyboard::{
+    Modifiers,
+    key,
+};
+use rdev::Key;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use tray_icon::menu::accelerator::{
+    self,
+    Accelerator,
+    Code,
+};
+
+/// Keys that can be bound, with their names and platform key codes.
+const KEYS: [(&str, Key, Code, key::Code); 50] = [
+    ("A", Key::KeyA, Code::KeyA, key::Code::KeyA),
+    ("B", Key::KeyB, Code::KeyB, key::Code::KeyB),
+    ("C", Key::KeyC, Code::KeyC, key::Code::KeyC),
+    ("D", Key::KeyD, Code::KeyD, key::Code::KeyD),
+    ("E", Key::KeyE, Code::KeyE, key::Code::KeyE),
+    ("F", Key::KeyF, Code::KeyF, key::Code::KeyF),
+    ("G", Key::KeyG, Code::KeyG, key::Code::KeyG),
+    ("H", Key::KeyH, Code::KeyH, key::Code::KeyH),
+    ("I", Key::KeyI, Code::KeyI, key::Code::KeyI),
+    ("J", Key::KeyJ, Code::KeyJ, key::Code::KeyJ),
+    ("K", Key::KeyK, Code::KeyK, key::Code::KeyK),
+    ("L", Key::KeyL, Code::KeyL, key::Code::KeyL),
+    ("M", Key::KeyM, Code::KeyM, key::Code::KeyM),
+    ("N", Key::KeyN, Code::KeyN, key::Code::KeyN),
+    ("O", Key::KeyO, Code::KeyO, key::Code::KeyO),
+    ("P", Key::KeyP, Code::KeyP, key::Code::KeyP),
+    ("Q", Key::KeyQ, Code::KeyQ, key::Code::KeyQ),
+    ("R", Key::KeyR, Code::KeyR, key::Code::KeyR),
+    ("S", Key::KeyS, Code::KeyS, key::Code::KeyS),
+    ("T", Key::KeyT, Code::KeyT, key::Code::KeyT),
+    ("U", Key::KeyU, Code::KeyU, key::Code::KeyU),
+    ("V", Key::KeyV, Code::KeyV, key::Code::KeyV),
+    ("W", Key::KeyW, Code::KeyW, key::Code::KeyW),
+    ("X", Key::KeyX, Code::KeyX, key::Code::KeyX),
+    ("Y", Key::KeyY, Code::KeyY, key::Code::KeyY),
+    ("Z", Key::KeyZ, Code::KeyZ, key::Code::KeyZ),
+    ("0", Key::Num0, Code::Digit0, key::Code::Digit0),
+    ("1", Key::Num1, Code::Digit1, key::Code::Digit1),
+    ("2", Key::Num2, Code::Digit2, key::Code::Digit2),
+    ("3", Key::Num3, Code::Digit3, key::Code::Digit3),
+    ("4", Key::Num4, Code::Digit4, key::Code::Digit4),
+    ("5", Key::Num5, Code::Digit5, key::Code::Digit5),
+    ("6", Key::Num6, Code::Digit6, key::Code::Digit6),
+    ("7", Key::Num7, Code::Digit7, key::Code::Digit7),
+    ("8", Key::Num8, Code::Digit8, key::Code::Digit8),
+    ("9", Key::Num9, Code::Digit9, key::Code::Digit9),
+    ("F1", Key::F1, Code::F1, key::Code::F1),
+    ("F2", Key::F2, Code::F2, key::Code::F2),
+    ("F3", Key::F3, Code::F3, key::Code::F3),
+    ("F4", Key::F4, Code::F4, key::Code::F4),
+    ("F5", Key::F5, Code::F5, key::Code::F5),
+    ("F6", Key::F6, Code::F6, key::Code::F6),
+    ("F7", Key::F7, Code::F7, key::Code::F7),
+    ("F8", Key::F8, Code::F8, key::Code::F8),
+    ("F9", Key::F9, Code::F9, key::Code::F9),
+    ("F10", Key::F10, Code::F10, key::Code::F10),
+    ("F11", Key::F11, Code::F11, key::Code::F11),
+    ("F12", Key::F12, Code::F12, key::Code::F12),
+    (
+        "PrintScreen",
+        Key::PrintScreen,
+        Code::PrintScreen,
+        key::Code::PrintScreen,
+    ),
+    ("Space", Key::Space, Code::Space, key::Code::Space),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Chord {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub logo: bool,
+    key: &'static str,
+}
+
+impl Chord {
+    pub fn new(ctrl: bool, alt: bool, shift: bool, logo: bool, key: Key) -> Option<Self> {
+        let (name, ..) = KEYS.iter().find(|(_, rdev_key, ..)| *rdev_key == key)?;
+
+        Some(Self {
+            ctrl,
+            alt,
+            shift,
+            logo,
+            key: name,
+        })
+        .filter(Self::is_valid)
+    }
+
+    /// Chord of a key press in a window, named after the physical key.
+    ///
+    /// The logical key depends on the modifiers, like `!` for Shift+1, which would never match
+    /// the keys reported by the global listener.
+    pub fn from_iced(physical_key: &key::Physical, modifiers: Modifiers) -> Option<Self> {
+        let key::Physical::Code(code) = physical_key else {
+            return None;
+        };
+
+        let (name, ..) = KEYS.iter().find(|(.., iced_code)| iced_code == code)?;
+
+        Some(Self {
+            ctrl: modifiers.control(),
+            alt: modifiers.alt(),
+            shift: modifiers.shift(),
+            logo: modifiers.logo(),
+            key: name,
+        })
+        .filter(Self::is_valid)
+    }
+
+    pub fn accelerator(&self) -> Accelerator {
+        let mut modifiers = accelerator::Modifiers::empty();
+        modifiers.set(accelerator::Modifiers::CONTROL, self.ctrl);
+        modifiers.set(accelerator::Modifiers::ALT, self.alt);
+        modifiers.set(accelerator::Modifiers::SHIFT, self.shift);
+        modifiers.set(accelerator::Modifiers::SUPER, self.logo);
+
+        let code = KEYS
+            .iter()
+            .find_map(|(name, _, code, _)| (*name == self.key).then_some(*code))
+            .expect("Chord key must be known");
+
+        Accelerator::new(Some(modifiers), code)
+    }
+
+    /// Plain keys would swallow typing, so they need Ctrl, Alt or Super,
+    /// except function keys and Print Screen.
+    fn is_valid(&self) -> bool {
+        let is_function_key = self.key.len() > 1 && self.key.starts_with('F');
+
+        self.ctrl || self.alt || self.logo || is_function_key || self.key == "PrintScreen"
+    }
+}
+
+impl Display for Chord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let modifiers = [
+            (self.ctrl, "Ctrl"),
+            (self.alt, "Alt"),
+            (self.shift, "Shift"),
+            (self.logo, "Super"),
+        ];
+
+        for (_, name) in modifiers.iter().filter(|(pressed, _)| *pressed) {
+            write!(f, "{name}+")?;
+        }
+
+        write!(f, "{}", self.key)
+    }
+}
+
+impl TryFrom<&str> for Chord {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        let mut chord = Self {
+            ctrl: false,
+            alt: false,
+            shift: false,
+            logo: false,
+            key: "",
+        };
+
+        for part in value.split('+').map(str::trim) {
+            match part.to_lowercase().as_str() {
+                "ctrl" | "control" => chord.ctrl = true,
+                "alt" | "option" => chord.alt = true,
+                "shift" => chord.shift = true,
+                "super" | "logo" | "cmd" | "win" | "meta" => chord.logo = true,
+                key => {
+                    let Some((name, ..)) =
+                        KEYS.iter().find(|(name, ..)| name.to_lowercase() == key)
+                    else {
+                        bail!("Unknown key: {part}");
+                    };
+                    if !chord.key.is_empty() {
+                        bail!("Hotkey must have a single key: {value}");
+                    }
+                    chord.key = name;
+                }
+            }
+        }
+
+        if chord.key.is_empty() {
+            bail!("Hotkey is missing a key: {value}");
+        }
+
+        if !chord.is_valid() {
+            bail!("Hotkey needs Ctrl, Alt or Super: {value}");
+        }
+
+        Ok(chord)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotkeyAction {
+    CaptureArea,
+    DelayedCapture,
+    CaptureFullscreen,
+    CaptureWindow,
+    RepeatLastRegion,
+    OpenSettings,
+}
+
+impl HotkeyAction {
+    pub const ALL: [HotkeyAction; 6] = [
+        HotkeyAction::CaptureArea,
+        HotkeyAction::DelayedCapture,
+        HotkeyAction::CaptureFullscreen,
+        HotkeyAction::CaptureWindow,
+        HotkeyAction::RepeatLastRegion,
+        HotkeyAction::OpenSettings,
+    ];
+}
+
+impl Display for HotkeyAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HotkeyAction::CaptureArea => write!(f, "Capture Area"),
+            HotkeyAction::DelayedCapture => write!(f, "Delayed Capture"),
+            HotkeyAction::CaptureFullscreen => write!(f, "Capture Fullscreen"),
+            HotkeyAction::CaptureWindow => write!(f, "Capture Window"),
+            HotkeyAction::RepeatLastRegion => write!(f, "Repeat Last Region"),
+            HotkeyAction::OpenSettings => write!(f, "Open Settings"),
+        }
+    }
+}
+
+/// Chords used inside the capture window, which hotkeys must not shadow.
+const RESERVED: [(&str, &str); 3] = [
+    ("Ctrl+Z", "Undo"),
+    ("Ctrl+Shift+Z", "Redo"),
+    ("Ctrl+Y", "Redo"),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Hotkeys {
+    #[serde(with = "binding")]
+    pub capture_area: Option<Chord>,
+    #[serde(with = "binding")]
+    pub delayed_capture: Option<Chord>,
+    #[serde(with = "binding")]
+    pub capture_fullscreen: Option<Chord>,
+    #[serde(with = "binding")]
+    pub capture_window: Option<Chord>,
+    #[serde(with = "binding")]
+    pub repeat_last_region: Option<Chord>,
+    #[serde(with = "binding")]
+    pub open_settings: Option<Chord>,
+}
+
+impl Default for Hotkeys {
+    fn default() -> Self {
+        let chord = |value| Chord::try_from(value).ok();
+
+        Self {
+            capture_area: chord("Alt+Shift+S"),
+            delayed_capture: chord("Alt+Shift+D"),
+            capture_fullscreen: chord("Alt+Shift+F"),
+            capture_window: chord("Alt+Shift+W"),
+            repeat_last_region: chord("Alt+Shift+R"),
+            open_settings: chord("Alt+Shift+O"),
+        }
+    }
+}
+
+impl Hotkeys {
+    pub fn get(&self, action: HotkeyAction) -> Option<Chord> {
+        match action {
+            HotkeyAction::CaptureArea => self.capture_area,
+            HotkeyAction::DelayedCapture => self.delayed_capture,
+            HotkeyAction::CaptureFullscreen => self.capture_fullscreen,
+            HotkeyAction::CaptureWindow => self.capture_window,
+            HotkeyAction::RepeatLastRegion => self.repeat_last_region,
+            HotkeyAction::OpenSettings => self.open_settings,
+        }
+    }
+
+    pub fn get_mut(&mut self, action: HotkeyAction) -> &mut Option<Chord> {
+        match action {
+            HotkeyAction::CaptureArea => &mut self.capture_area,
+            HotkeyAction::DelayedCapture => &mut self.delayed_capture,
+            HotkeyAction::CaptureFullscreen => &mut self.capture_fullscreen,
+            HotkeyAction::CaptureWindow => &mut self.capture_window,
+            HotkeyAction::RepeatLastRegion => &mut self.repeat_last_region,
+            HotkeyAction::OpenSettings => &mut self.open_settings,
+        }
+    }
+
+    pub fn action(&self, chord: Chord) -> Option<HotkeyAction> {
+        HotkeyAction::ALL
+            .into_iter()
+            .find(|action| self.get(*action) == Some(chord))
+    }
+
+    /// Binds the chord to the action, unless it is already taken.
+    pub fn bind(&mut self, action: HotkeyAction, chord: Chord) -> Result<()> {
+        if let Some(other) = self.action(chord).filter(|other| *other != action) {
+            bail!("{chord} is already used by {other}");
+        }
+
+        if let Some((_, name)) = RESERVED
+            .iter()
+            .find(|(reserved, _)| Chord::try_from(*reserved).ok() == Some(chord))
+        {
+            bail!("{chord} is reserved for {name}");
+        }
+
+        *self.get_mut(action) = Some(chord);
+
+        Ok(())
+    }
+}
+
+/// Stores a binding as its chord, or an empty string when unbound.
+mod binding {
+    use serde::{
+        Deserialize,
+        Deserializer,
+        Serializer,
+    };
+
+    use super::Chord;
+
+    pub fn serialize<S: Serializer>(
+        chord: &Option<Chord>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&chord.map(|chord| chord.to_string()).unwrap_or_default())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Chord>, D::Error> {
+        let value = String::deserialize(deserializer)?;
+
+        if value.is_empty() {
+            return Ok(None);
+        }
+
+        Chord::try_from(value.as_str())
+            .map(Some)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(value: &str) -> Chord {
+        Chord::try_from(value).unwrap()
+    }
+
+    #[test]
+    fn parses_chords() {
+        let parsed = chord("Ctrl+Shift+S");
+        assert!(parsed.ctrl && parsed.shift && !parsed.alt && !parsed.logo);
+        assert_eq!(parsed.key, "S");
+
+        assert_eq!(chord(" ctrl + shift + s "), parsed);
+        assert_eq!(chord("Control+Shift+S"), parsed);
+        assert_eq!(chord("Option+1"), chord("Alt+1"));
+        assert_eq!(chord("Cmd+Space"), chord("Super+Space"));
+        assert_eq!(chord("Win+Space"), chord("Super+Space"));
+    }
+
+    #[test]
+    fn rejects_invalid_chords() {
+        assert!(Chord::try_from("Ctrl+Shift").is_err());
+        assert!(Chord::try_from("").is_err());
+        assert!(Chord::try_from("Ctrl+Tab").is_err());
+        assert!(Chord::try_from("Ctrl+A+B").is_err());
+        assert!(Chord::try_from("A").is_err());
+        assert!(Chord::try_from("Shift+A").is_err());
+    }
+
+    #[test]
+    fn allows_function_keys_without_modifiers() {
+        assert!(Chord::try_from("F1").is_ok());
+        assert!(Chord::try_from("Shift+F12").is_ok());
+        assert!(Chord::try_from("PrintScreen").is_ok());
+    }
+
+    #[test]
+    fn displays_chords() {
+        assert_eq!(
+            chord("shift+alt+ctrl+super+s").to_string(),
+            "Ctrl+Alt+Shift+Super+S"
+        );
+
+        for value in ["Alt+Shift+S", "Ctrl+1", "F5", "Super+PrintScreen"] {
+            assert_eq!(chord(value).to_string(), value);
+            assert_eq!(chord(&chord(value).to_string()), chord(value));
+        }
+    }
+
+    #[test]
+    fn matches_physical_keys() {
+        let physical = key::Physical::Code(key::Code::Digit1);
+
+        assert_eq!(
+            Chord::from_iced(&physical, Modifiers::ALT | Modifiers::SHIFT),
+            Some(chord("Alt+Shift+1"))
+        );
+        assert_eq!(
+            Chord::new(false, true, true, false, Key::Num1),
+            Some(chord("Alt+Shift+1"))
+        );
+        assert_eq!(Chord::from_iced(&physical, Modifiers::SHIFT), None);
+    }
+}
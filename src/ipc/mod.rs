@@ -0,0 +1,149 @@
+pub mod protocol;
+
+use std::io::{
+    BufRead,
+    BufReader as SyncBufReader,
+    Write,
+};
+
+use anyhow::{
+    Context,
+    Result,
+    bail,
+};
+use iced::{
+    futures::{
+        SinkExt,
+        Stream,
+        channel::mpsc::Sender,
+    },
+    stream,
+};
+use interprocess::local_socket::{
+    self,
+    GenericNamespaced,
+    ListenerOptions,
+    ToNsName,
+    traits::{
+        Stream as _,
+        tokio::{
+            Listener,
+            Stream as _,
+        },
+    },
+};
+use protocol::{
+    PROTOCOL_VERSION,
+    Request,
+    RequestEnvelope,
+    Responder,
+    Response,
+    ResponseEnvelope,
+};
+use tokio::io::{
+    AsyncBufReadExt,
+    AsyncWriteExt,
+    BufReader,
+};
+
+use crate::{
+    Message,
+    consts::APPNAME,
+};
+
+/// Connects to the running instance, if there is one.
+pub fn connect() -> Option<local_socket::Stream> {
+    let name = APPNAME
+        .to_ns_name::<GenericNamespaced>()
+        .expect("Name must be valid");
+
+    local_socket::Stream::connect(name).ok()
+}
+
+/// Sends the request to the running instance and waits for its response.
+pub fn send(stream: local_socket::Stream, request: Request) -> Result<Response> {
+    let mut line = serde_json::to_string(&RequestEnvelope::from(request))
+        .context("Failed to encode request")?;
+    line.push('\n');
+
+    let mut stream = SyncBufReader::new(stream);
+
+    stream
+        .get_mut()
+        .write_all(line.as_bytes())
+        .context("Failed to send request to the running instance")?;
+
+    let mut line = String::new();
+    stream
+        .read_line(&mut line)
+        .context("Failed to read response from the running instance")?;
+
+    let envelope = serde_json::from_str::<ResponseEnvelope>(&line)
+        .context("Invalid response from the running instance")?;
+
+    if envelope.version != PROTOCOL_VERSION {
+        bail!(
+            "Running instance speaks protocol version {}, expected {PROTOCOL_VERSION}",
+            envelope.version
+        );
+    }
+
+    Ok(envelope.response)
+}
+
+pub fn ipc_listener() -> impl Stream<Item = Message> {
+    stream::channel(1, async |output| {
+        let name = APPNAME
+            .to_ns_name::<GenericNamespaced>()
+            .expect("Name should be created");
+
+        let listner_opts = ListenerOptions::new().name(name);
+
+        let listener = listner_opts
+            .create_tokio()
+            .expect("Listener should be created");
+
+        loop {
+            if let Ok(stream) = listener.accept().await {
+                tokio::spawn(handle_connection(stream, output.clone()));
+            }
+        }
+    })
+}
+
+async fn handle_connection(stream: local_socket::tokio::Stream, mut output: Sender<Message>) {
+    let (reader, mut writer) = stream.split();
+    let mut lines = BufReader::new(reader).lines();
+
+    while let Ok(Some(line)) = lines.next_line().await {
+        let response = match serde_json::from_str::<RequestEnvelope>(&line) {
+            Ok(envelope) if envelope.version == PROTOCOL_VERSION => {
+                let (responder, receiver) = Responder::new();
+
+                let _ = output.send(Message::Ipc(envelope.request, responder)).await;
+
+                receiver.await.unwrap_or_else(|_| Response::Error {
+                    message: String::from("Request was dropped"),
+                })
+            }
+            Ok(envelope) => Response::Error {
+                message: format!(
+                    "Unsupported protocol version {}, expected {PROTOCOL_VERSION}",
+                    envelope.version
+                ),
+            },
+            Err(err) => Response::Error {
+                message: format!("Invalid request: {err}"),
+            },
+        };
+
+        let Ok(mut line) = serde_json::to_string(&ResponseEnvelope::from(response)) else {
+            break;
+        };
+        line.push('\n');
+
+        if writer.write_all(line.as_bytes()).await.is_err() {
+            break;
+        }
+    }
+}
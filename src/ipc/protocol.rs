@@ -0,0 +1,137 @@
+//! Line-delimited JSON protocol spoken over the local socket.
+//!
+//! Every line sent by a client is a [`RequestEnvelope`], and the daemon answers each one
+//! with exactly one [`ResponseEnvelope`] line, in order:
+//!
+//! ```text
+//! > {"version":1,"type":"capture","target":"full_screen","output":"/tmp/shot.png","copy_to_clipboard":false}
+//! < {"version":1,"status":"saved","path":"/tmp/shot.png"}
+//! ```
+
+use std::{
+    path::PathBuf,
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+
+use iced::futures::channel::oneshot;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use serde_json::Value;
+
+use crate::cli::CaptureArgs;
+
+pub const PROTOCOL_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Request {
+    /// Opens the interactive capture window and replies once it is closed.
+    OpenCapture {
+        #[serde(default)]
+        delay: u32,
+    },
+    OpenSettings,
+    /// Opens an image or a `.capter` project in the annotation editor.
+    OpenFile {
+        path: PathBuf,
+    },
+    /// Captures without opening the capture window.
+    Capture(CaptureArgs),
+    GetConfig,
+    /// Sets a config key, using `.` to separate nested keys.
+    SetConfig {
+        key: String,
+        value: Value,
+    },
+    Exit,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum Response {
+    Ok,
+    Saved { path: PathBuf },
+    SavedAll { paths: Vec<PathBuf> },
+    Config { config: Value },
+    Error { message: String },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RequestEnvelope {
+    pub version: u32,
+    #[serde(flatten)]
+    pub request: Request,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResponseEnvelope {
+    pub version: u32,
+    #[serde(flatten)]
+    pub response: Response,
+}
+
+/// Sends the [`Response`] for a request back to the connection it came from.
+#[derive(Debug, Clone)]
+pub struct Responder(Arc<Mutex<Option<oneshot::Sender<Response>>>>);
+
+impl Responder {
+    pub fn new() -> (Self, oneshot::Receiver<Response>) {
+        let (sender, receiver) = oneshot::channel();
+        (Self(Arc::new(Mutex::new(Some(sender)))), receiver)
+    }
+
+    /// Replies to the request. Only the first reply is delivered.
+    pub fn respond(&self, response: Response) {
+        if let Some(sender) = self.0.lock().ok().and_then(|mut sender| sender.take()) {
+            let _ = sender.send(response);
+        }
+    }
+}
+
+impl From<anyhow::Result<PathBuf>> for Response {
+    fn from(result: anyhow::Result<PathBuf>) -> Self {
+        match result {
+            Ok(path) => Self::Saved { path },
+            Err(err) => Self::Error {
+                message: err.to_string(),
+            },
+        }
+    }
+}
+
+impl From<anyhow::Result<Vec<PathBuf>>> for Response {
+    fn from(result: anyhow::Result<Vec<PathBuf>>) -> Self {
+        match result {
+            Ok(mut paths) if paths.len() == 1 => Self::Saved {
+                path: paths.remove(0),
+            },
+            Ok(paths) => Self::SavedAll { paths },
+            Err(err) => Self::Error {
+                message: err.to_string(),
+            },
+        }
+    }
+}
+
+impl From<Request> for RequestEnvelope {
+    fn from(request: Request) -> Self {
+        Self {
+            version: PROTOCOL_VERSION,
+            request,
+        }
+    }
+}
+
+impl From<Response> for ResponseEnvelope {
+    fn from(response: Response) -> Self {
+        Self {
+            version: PROTOCOL_VERSION,
+            response,
+        }
+    }
+}
@@ -13,7 +13,10 @@ use rdev::{
     listen,
 };
 
-use crate::Message;
+use crate::{
+    Message,
+    hotkeys::Chord,
+};
 
 pub fn global_key_listener() -> impl Stream<Item = Message> {
     stream::channel(1, async |mut output| {
@@ -25,26 +28,32 @@ pub fn global_key_listener() -> impl Stream<Item = Message> {
             });
         });
 
+        let mut ctrl_pressed = false;
         let mut alt_pressed = false;
         let mut shift_pressed = false;
+        let mut logo_pressed = false;
 
         loop {
             let event = receiver.select_next_some().await;
             match event.event_type {
                 EventType::KeyPress(key) => match key {
+                    Key::ControlLeft | Key::ControlRight => ctrl_pressed = true,
                     Key::Alt => alt_pressed = true,
                     Key::ShiftLeft | Key::ShiftRight => shift_pressed = true,
-                    Key::KeyS if alt_pressed && shift_pressed => {
-                        let _ = output.send(Message::OpenCaptureWindow).await;
-                    }
-                    Key::KeyO if alt_pressed && shift_pressed => {
-                        let _ = output.send(Message::OpenSettingsWindow).await;
+                    Key::MetaLeft | Key::MetaRight => logo_pressed = true,
+                    key => {
+                        if let Some(chord) =
+                            Chord::new(ctrl_pressed, alt_pressed, shift_pressed, logo_pressed, key)
+                        {
+                            let _ = output.send(Message::HotkeyPressed(chord)).await;
+                        }
                     }
-                    _ => {}
                 },
                 EventType::KeyRelease(key) => match key {
+                    Key::ControlLeft | Key::ControlRight => ctrl_pressed = false,
                     Key::Alt => alt_pressed = false,
                     Key::ShiftLeft | Key::ShiftRight => shift_pressed = false,
+                    Key::MetaLeft | Key::MetaRight => logo_pressed = false,
                     _ => {}
                 },
                 _ => {}
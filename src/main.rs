@@ -1,7 +1,13 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod action;
+mod cli;
 mod consts;
+mod countdown;
+mod desktop;
+mod file_name;
+mod headless;
+mod hotkeys;
 mod ipc;
 mod key_listener;
 mod notify;
@@ -17,9 +23,21 @@ mod config;
 mod settings;
 
 mod organize_type;
+mod output_format;
+
+use std::{
+    collections::BTreeMap,
+    path::PathBuf,
+    time::{
+        Duration,
+        Instant,
+    },
+};
 
-use std::collections::BTreeMap;
-
+use cli::{
+    Command,
+    USAGE,
+};
 use config::Config;
 use consts::{
     APPNAME,
@@ -28,50 +46,117 @@ use consts::{
     MEDIUM_FONT,
     MEDIUM_FONT_TTF,
 };
+use countdown::Countdown;
+use hotkeys::Chord;
 use iced::{
     Task,
+    Vector,
     daemon,
     window::Id,
 };
-use interprocess::local_socket::{
-    self,
-    GenericNamespaced,
-    ToNsName,
-    traits::Stream,
+use ipc::protocol::{
+    Responder,
+    Response,
 };
 use tray_icon::create_tray_icon;
 use window::AppWindow;
 
 fn main() -> Result<(), iced::Error> {
-    let name = APPNAME
-        .to_ns_name::<GenericNamespaced>()
-        .expect("Name must be valid");
+    let command = match Command::parse(std::env::args().skip(1)) {
+        Ok(command) => command,
+        Err(err) => {
+            eprintln!("{err}\n\n{USAGE}");
+            std::process::exit(2);
+        }
+    };
+
+    match &command {
+        Command::Help => {
+            println!("{USAGE}");
+            return Ok(());
+        }
+        Command::Version => {
+            println!("{APPNAME} {}", env!("CARGO_PKG_VERSION"));
+            return Ok(());
+        }
+        _ => {}
+    }
 
-    if local_socket::Stream::connect(name).is_ok() {
+    if let Some(stream) = ipc::connect()
+        && let Some(request) = command.request()
+    {
+        match ipc::send(stream, request) {
+            Ok(Response::Ok) => {}
+            Ok(Response::Saved { path }) => println!("{}", path.display()),
+            Ok(Response::SavedAll { paths }) => {
+                paths.iter().for_each(|path| println!("{}", path.display()))
+            }
+            Ok(Response::Config { config }) => println!("{config}"),
+            Ok(Response::Error { message }) => {
+                eprintln!("{message}");
+                std::process::exit(1);
+            }
+            Err(err) => {
+                eprintln!("{err}");
+                std::process::exit(1);
+            }
+        }
         return Ok(());
     };
 
+    match &command {
+        Command::Capture(args) if args.target.is_some() => {
+            let config = Config::load().map(|(config, _)| config).unwrap_or_default();
+
+            std::thread::sleep(Duration::from_secs(args.delay.into()));
+
+            match headless::capture(args, &config) {
+                Ok(paths) => {
+                    paths.iter().for_each(|path| println!("{}", path.display()));
+                    return Ok(());
+                }
+                Err(err) => {
+                    eprintln!("{err}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::Quit => {
+            eprintln!("{APPNAME} is not running");
+            std::process::exit(1);
+        }
+        _ => {}
+    }
+
+    let startup_message = command.startup_message();
+
+    let config = Config::load().map(|(config, _)| config).unwrap_or_default();
+
     #[cfg(not(target_os = "linux"))]
-    let _tray_icon = create_tray_icon();
+    create_tray_icon(&config.hotkeys, config.capture_delay);
 
     #[cfg(target_os = "linux")]
-    std::thread::spawn(|| {
+    std::thread::spawn(move || {
         gtk::init().expect("GTK must be initialized");
-        let _tray_icon = create_tray_icon();
+        create_tray_icon(&config.hotkeys, config.capture_delay);
         gtk::main();
     });
 
-    daemon(App::new, App::update, App::view)
-        .font(MEDIUM_FONT_TTF)
-        .font(BOLD_FONT_TTF)
-        .font(ICON_FONT_TTF)
-        .default_font(MEDIUM_FONT)
-        .title(App::title)
-        .style(App::style)
-        .theme(App::theme)
-        .subscription(App::subscription)
-        .antialiasing(true)
-        .run()
+    daemon(
+        move || App::new(startup_message.clone()),
+        App::update,
+        App::view,
+    )
+    .font(MEDIUM_FONT_TTF)
+    .font(BOLD_FONT_TTF)
+    .font(ICON_FONT_TTF)
+    .default_font(MEDIUM_FONT)
+    .title(App::title)
+    .style(App::style)
+    .theme(App::theme)
+    .subscription(App::subscription)
+    .antialiasing(true)
+    .run()
 }
 
 pub struct App {
@@ -80,6 +165,9 @@ pub struct App {
 
     config: Config,
     windows: BTreeMap<Id, AppWindow>,
+    countdown: Option<Countdown>,
+    capture_responders: Vec<Responder>,
+    last_hotkey: Option<(Chord, Instant)>,
 }
 
 #[derive(Debug, Clone)]
@@ -87,7 +175,22 @@ pub enum Message {
     ConfigInitialized,
     OpenSettingsWindow,
     OpenCaptureWindow,
+    PickFile,
+    OpenFile(PathBuf),
+    FileDropped(Id, PathBuf),
+    StartCountdown(u32, Box<Message>),
+    CountdownTick,
+    CancelCountdown,
+    QuickCapture(cli::CaptureTarget),
+    HotkeyPressed(Chord),
+    Ipc(ipc::protocol::Request, Responder),
+    /// Result of a capture made without the capture window, sent back to the IPC client if any.
+    HeadlessCaptured(Result<Vec<PathBuf>, String>, Option<Responder>),
     Undo,
+    Redo,
+    Delete,
+    Nudge(Vector),
+    CopyColor,
     Done,
     Cancel,
     RequestClose(Id),
@@ -98,7 +201,7 @@ pub enum Message {
 }
 
 impl App {
-    pub fn new() -> (App, Task<Message>) {
+    pub fn new(startup_message: Option<Message>) -> (App, Task<Message>) {
         let (config, task) = match Config::load() {
             Ok((config, is_first_creation)) => (
                 config,
@@ -119,8 +222,11 @@ impl App {
 
                 config,
                 windows: BTreeMap::new(),
+                countdown: None,
+                capture_responders: Vec::new(),
+                last_hotkey: None,
             },
-            task,
+            task.chain(startup_message.map_or_else(Task::none, Task::done)),
         )
     }
 }
@@ -3,6 +3,8 @@ use serde::{
     Serialize,
 };
 
+use crate::file_name::DEFAULT_TEMPLATE;
+
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum OrgranizeMode {
     #[default]
@@ -17,6 +19,21 @@ impl OrgranizeMode {
         OrgranizeMode::ByYear,
         OrgranizeMode::ByYearAndMonth,
     ];
+
+    /// File name template which lays out folders for this mode.
+    pub fn template(&self) -> &'static str {
+        match self {
+            OrgranizeMode::Flat => DEFAULT_TEMPLATE,
+            OrgranizeMode::ByYear => "{date:%Y}/{app}_{date}.{ext}",
+            OrgranizeMode::ByYearAndMonth => "{date:%Y/%m}/{app}_{date}.{ext}",
+        }
+    }
+
+    pub fn from_template(template: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|mode| mode.template() == template)
+    }
 }
 
 impl std::fmt::Display for OrgranizeMode {
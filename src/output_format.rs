@@ -0,0 +1,129 @@
+use std::{
+    fs::File,
+    io::BufWriter,
+    path::Path,
+};
+
+use anyhow::{
+    Context,
+    Result,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use xcap::image::{
+    DynamicImage,
+    ImageFormat,
+    RgbaImage,
+    codecs::jpeg::JpegEncoder,
+};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OutputFormat {
+    #[default]
+    Png,
+    Jpeg,
+    WebpLossless,
+    WebpLossy,
+    Bmp,
+    Tiff,
+    Qoi,
+}
+
+impl OutputFormat {
+    pub const ALL: [OutputFormat; 7] = [
+        OutputFormat::Png,
+        OutputFormat::Jpeg,
+        OutputFormat::WebpLossless,
+        OutputFormat::WebpLossy,
+        OutputFormat::Bmp,
+        OutputFormat::Tiff,
+        OutputFormat::Qoi,
+    ];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Png => "png",
+            OutputFormat::Jpeg => "jpg",
+            OutputFormat::WebpLossless | OutputFormat::WebpLossy => "webp",
+            OutputFormat::Bmp => "bmp",
+            OutputFormat::Tiff => "tiff",
+            OutputFormat::Qoi => "qoi",
+        }
+    }
+
+    /// Whether the quality setting applies to this format.
+    pub fn is_lossy(&self) -> bool {
+        matches!(self, OutputFormat::Jpeg | OutputFormat::WebpLossy)
+    }
+
+    /// Guesses the format from the extension of `path`.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+
+        match extension.as_str() {
+            "png" => Some(OutputFormat::Png),
+            "jpg" | "jpeg" => Some(OutputFormat::Jpeg),
+            "webp" => Some(OutputFormat::WebpLossless),
+            "bmp" => Some(OutputFormat::Bmp),
+            "tif" | "tiff" => Some(OutputFormat::Tiff),
+            "qoi" => Some(OutputFormat::Qoi),
+            _ => None,
+        }
+    }
+
+    /// Encodes the image to `path`, using `quality` (1 to 100) for lossy formats.
+    pub fn save(&self, image: &RgbaImage, path: &Path, quality: u8) -> Result<()> {
+        let quality = quality.clamp(1, 100);
+
+        match self {
+            OutputFormat::Jpeg => {
+                // JPEG has no alpha channel
+                let image = DynamicImage::ImageRgba8(image.clone()).to_rgb8();
+                let file = File::create(path).context("Failed to create image file")?;
+
+                JpegEncoder::new_with_quality(BufWriter::new(file), quality)
+                    .encode_image(&image)
+                    .context("Failed to encode JPEG")
+            }
+            OutputFormat::WebpLossy => {
+                let data = webp::Encoder::from_rgba(image.as_raw(), image.width(), image.height())
+                    .encode(quality as f32);
+
+                std::fs::write(path, &*data).context("Failed to write WebP")
+            }
+            OutputFormat::Png
+            | OutputFormat::WebpLossless
+            | OutputFormat::Bmp
+            | OutputFormat::Tiff
+            | OutputFormat::Qoi => {
+                let format = match self {
+                    OutputFormat::WebpLossless => ImageFormat::WebP,
+                    OutputFormat::Bmp => ImageFormat::Bmp,
+                    OutputFormat::Tiff => ImageFormat::Tiff,
+                    OutputFormat::Qoi => ImageFormat::Qoi,
+                    _ => ImageFormat::Png,
+                };
+
+                image
+                    .save_with_format(path, format)
+                    .context("Failed to encode image")
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OutputFormat::Png => write!(f, "PNG"),
+            OutputFormat::Jpeg => write!(f, "JPEG"),
+            OutputFormat::WebpLossless => write!(f, "WebP (Lossless)"),
+            OutputFormat::WebpLossy => write!(f, "WebP (Lossy)"),
+            OutputFormat::Bmp => write!(f, "BMP"),
+            OutputFormat::Tiff => write!(f, "TIFF"),
+            OutputFormat::Qoi => write!(f, "QOI"),
+        }
+    }
+}
@@ -7,6 +7,8 @@ impl Settings {
     pub fn init(config: &Config) -> Self {
         Self {
             folder_path: config.truncate_folder_path(),
+            recording_hotkey: None,
+            hotkey_error: None,
         }
     }
 }
@@ -3,13 +3,20 @@ mod update;
 mod view;
 
 use crate::{
+    hotkeys::{
+        Chord,
+        HotkeyAction,
+    },
     organize_type::OrgranizeMode,
+    output_format::OutputFormat,
     theme::Theme,
 };
 
 #[derive(Debug)]
 pub struct Settings {
     folder_path: String,
+    recording_hotkey: Option<HotkeyAction>,
+    hotkey_error: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -19,9 +26,25 @@ pub enum Message {
     UpdateTheme(Theme),
     ToggleShowNotification(bool),
     UpdateOrganizeMode(OrgranizeMode),
+    UpdateFileNameTemplate(String),
+    UpdateCaptureDelay(u32),
+    ToggleSpanAllMonitors(bool),
+    UpdateOutputFormat(OutputFormat),
+    UpdateImageQuality(u8),
+    ToggleExportSvg(bool),
+    ToggleSaveProject(bool),
+    RecordHotkey(HotkeyAction),
+    HotkeyRecorded(Chord),
+    ClearHotkey(HotkeyAction),
     RequestExit,
 }
 
 pub enum Request {
     Exit,
 }
+
+impl Settings {
+    pub fn is_recording_hotkey(&self) -> bool {
+        self.recording_hotkey.is_some()
+    }
+}
@@ -33,7 +33,49 @@ impl Settings {
                 config.show_notification = show_notification;
             }
             Message::UpdateOrganizeMode(organize_type) => {
-                config.organize_mode = organize_type;
+                config.file_name_template = organize_type.template().to_string();
+            }
+            Message::UpdateFileNameTemplate(template) => {
+                config.file_name_template = template;
+            }
+            Message::UpdateCaptureDelay(capture_delay) => {
+                config.capture_delay = capture_delay;
+            }
+            Message::ToggleSpanAllMonitors(span_all_monitors) => {
+                config.span_all_monitors = span_all_monitors;
+            }
+            Message::UpdateOutputFormat(output_format) => {
+                config.output_format = output_format;
+            }
+            Message::UpdateImageQuality(image_quality) => {
+                config.image_quality = image_quality;
+            }
+            Message::ToggleExportSvg(export_svg) => {
+                config.export_svg = export_svg;
+            }
+            Message::ToggleSaveProject(save_project) => {
+                config.save_project = save_project;
+            }
+            Message::RecordHotkey(action) => {
+                self.hotkey_error = None;
+                self.recording_hotkey = match self.recording_hotkey {
+                    Some(recording) if recording == action => None,
+                    _ => Some(action),
+                };
+            }
+            Message::HotkeyRecorded(chord) => {
+                if let Some(action) = self.recording_hotkey.take() {
+                    self.hotkey_error = config
+                        .hotkeys
+                        .bind(action, chord)
+                        .err()
+                        .map(|err| err.to_string());
+                }
+            }
+            Message::ClearHotkey(action) => {
+                *config.hotkeys.get_mut(action) = None;
+                self.recording_hotkey = None;
+                self.hotkey_error = None;
             }
             Message::RequestExit => {
                 return Action::requests([Request::Exit]);
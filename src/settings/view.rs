@@ -1,3 +1,4 @@
+use chrono::Local;
 use iced::{
     Alignment::Center,
     Length,
@@ -8,9 +9,12 @@ use iced::{
         PickList,
         Row,
         Scrollable,
+        Slider,
         Space,
         Text,
+        TextInput,
         Toggler,
+        text::IntoFragment,
     },
 };
 
@@ -22,7 +26,15 @@ use crate::{
         FOLDER_ICON_ICON,
         ICON_FONT,
     },
+    file_name::{
+        CaptureInfo,
+        CaptureKind,
+        DEFAULT_TEMPLATE,
+        render,
+    },
+    hotkeys::HotkeyAction,
     organize_type::OrgranizeMode,
+    output_format::OutputFormat,
     settings::{
         Message,
         Settings,
@@ -31,6 +43,7 @@ use crate::{
         Element,
         Theme,
         button::ButtonClass,
+        text::TextClass,
     },
 };
 
@@ -49,57 +62,163 @@ impl Settings {
             )
             .align_y(Center);
 
-        let body = Scrollable::new(
-            Column::new()
-                .push(list_item(
-                    "Theme",
-                    PickList::new(&Theme::ALL[..], Some(&config.theme), Message::UpdateTheme)
-                        .text_size(TEXT_SIZE)
-                        .into(),
-                ))
-                .push(list_item(
-                    "Show Notification",
-                    Toggler::new(config.show_notification)
-                        .size(22)
-                        .on_toggle(Message::ToggleShowNotification)
+        let mut body_column = Column::new()
+            .push(list_item(
+                "Theme",
+                PickList::new(&Theme::ALL[..], Some(&config.theme), Message::UpdateTheme)
+                    .text_size(TEXT_SIZE)
+                    .into(),
+            ))
+            .push(list_item(
+                "Show Notification",
+                Toggler::new(config.show_notification)
+                    .size(22)
+                    .on_toggle(Message::ToggleShowNotification)
+                    .into(),
+            ))
+            .push(list_item(
+                "Screenshots Folder",
+                Row::new()
+                    .push(
+                        Button::new(
+                            Text::new(FOLDER_ICON_ICON)
+                                .font(ICON_FONT)
+                                .size(TEXT_SIZE)
+                                .center(),
+                        )
+                        .on_press(Message::OpenFolder),
+                    )
+                    .push(Space::new().width(10))
+                    .push(
+                        Button::new(
+                            Text::new(self.folder_path.as_str())
+                                .size(TEXT_SIZE)
+                                .center(),
+                        )
+                        .on_press(Message::UpdateFolderPath),
+                    )
+                    .into(),
+            ))
+            .push(list_item(
+                "Organize Mode",
+                PickList::new(
+                    &OrgranizeMode::ALL[..],
+                    OrgranizeMode::from_template(&config.file_name_template),
+                    Message::UpdateOrganizeMode,
+                )
+                .placeholder("Custom")
+                .text_size(TEXT_SIZE)
+                .into(),
+            ))
+            .push(list_item(
+                "File Name",
+                Column::new()
+                    .push(
+                        TextInput::new(DEFAULT_TEMPLATE, &config.file_name_template)
+                            .on_input(Message::UpdateFileNameTemplate)
+                            .size(TEXT_SIZE),
+                    )
+                    .push(Text::new(file_name_preview(config)).size(14))
+                    .spacing(5)
+                    .width(360)
+                    .into(),
+            ))
+            .push(list_item(
+                "Image Format",
+                PickList::new(
+                    &OutputFormat::ALL[..],
+                    Some(&config.output_format),
+                    Message::UpdateOutputFormat,
+                )
+                .text_size(TEXT_SIZE)
+                .into(),
+            ))
+            .extend(config.output_format.is_lossy().then(|| {
+                list_item(
+                    "Image Quality",
+                    Row::new()
+                        .push(
+                            Slider::new(1..=100, config.image_quality, Message::UpdateImageQuality)
+                                .width(200),
+                        )
+                        .push(Space::new().width(10))
+                        .push(Text::new(format!("{} %", config.image_quality)).size(TEXT_SIZE))
+                        .align_y(Center)
                         .into(),
-                ))
-                .push(list_item(
-                    "Screenshots Folder",
+                )
+            }))
+            .push(list_item(
+                "Also Save as SVG",
+                Toggler::new(config.export_svg)
+                    .size(22)
+                    .on_toggle(Message::ToggleExportSvg)
+                    .into(),
+            ))
+            .push(list_item(
+                "Also Save Project",
+                Toggler::new(config.save_project)
+                    .size(22)
+                    .on_toggle(Message::ToggleSaveProject)
+                    .into(),
+            ))
+            .push(list_item(
+                "Capture Delay",
+                Row::new()
+                    .push(
+                        Slider::new(1..=30, config.capture_delay, Message::UpdateCaptureDelay)
+                            .width(200),
+                    )
+                    .push(Space::new().width(10))
+                    .push(Text::new(format!("{} s", config.capture_delay)).size(TEXT_SIZE))
+                    .align_y(Center)
+                    .into(),
+            ))
+            .push(list_item(
+                "Span All Monitors",
+                Toggler::new(config.span_all_monitors)
+                    .size(22)
+                    .on_toggle(Message::ToggleSpanAllMonitors)
+                    .into(),
+            ))
+            .extend(HotkeyAction::ALL.into_iter().map(|action| {
+                let is_recording = self.recording_hotkey == Some(action);
+
+                let label = match config.hotkeys.get(action) {
+                    _ if is_recording => String::from("Press a hotkey..."),
+                    Some(chord) => chord.to_string(),
+                    None => String::from("None"),
+                };
+
+                list_item(
+                    action.to_string(),
                     Row::new()
                         .push(
-                            Button::new(
-                                Text::new(FOLDER_ICON_ICON)
-                                    .font(ICON_FONT)
-                                    .size(TEXT_SIZE)
-                                    .center(),
-                            )
-                            .on_press(Message::OpenFolder),
+                            Button::new(Text::new(label).size(TEXT_SIZE).center())
+                                .on_press(Message::RecordHotkey(action))
+                                .class(match is_recording {
+                                    true => ButtonClass::Selected,
+                                    false => ButtonClass::Default,
+                                }),
                         )
                         .push(Space::new().width(10))
                         .push(
-                            Button::new(
-                                Text::new(self.folder_path.as_str())
-                                    .size(TEXT_SIZE)
-                                    .center(),
-                            )
-                            .on_press(Message::UpdateFolderPath),
+                            Button::new(Text::new("Clear").size(TEXT_SIZE).center())
+                                .on_press(Message::ClearHotkey(action)),
                         )
                         .into(),
-                ))
-                .push(list_item(
-                    "Organize Mode",
-                    PickList::new(
-                        &OrgranizeMode::ALL[..],
-                        Some(&config.organize_mode),
-                        Message::UpdateOrganizeMode,
-                    )
-                    .text_size(TEXT_SIZE)
-                    .into(),
-                ))
-                .spacing(10),
-        )
-        .spacing(10);
+                )
+            }))
+            .spacing(10);
+
+        if let Some(error) = &self.hotkey_error {
+            body_column = body_column.push(
+                Text::new(error.as_str())
+                    .size(TEXT_SIZE)
+                    .class(TextClass::Custom(config.theme.palette().danger)),
+            );
+        }
+
+        let body = Scrollable::new(body_column).spacing(10);
 
         Column::new()
             .push(header)
@@ -110,7 +229,27 @@ impl Settings {
     }
 }
 
-fn list_item<'a>(label: &'a str, item: Element<'a, Message>) -> Element<'a, Message> {
+/// Renders the file name template for a sample window capture.
+fn file_name_preview(config: &Config) -> String {
+    let info = CaptureInfo {
+        kind: CaptureKind::Window,
+        window_title: Some(String::from("Firefox")),
+        monitor_name: Some(String::from("Monitor")),
+    };
+
+    let path = render(
+        &config.file_name_template,
+        &info,
+        (1920, 1080),
+        config.output_format.extension(),
+        1,
+        Local::now(),
+    );
+
+    format!("Preview: {}", path.display())
+}
+
+fn list_item<'a>(label: impl IntoFragment<'a>, item: Element<'a, Message>) -> Element<'a, Message> {
     Container::new(
         Row::new()
             .push(Text::new(label).size(22).font(BOLD_FONT))
@@ -1,16 +1,23 @@
+use std::time::Duration;
+
 use iced::{
+    Event,
     Subscription,
+    Vector,
+    event,
     keyboard::{
         self,
         Modifiers,
         key,
     },
+    time,
     window,
 };
 
 use crate::{
     App,
     Message,
+    hotkeys::Chord,
     ipc::ipc_listener,
     key_listener::global_key_listener,
     tray_icon::{
@@ -23,22 +30,71 @@ impl App {
     pub fn subscription(&self) -> Subscription<Message> {
         let window_events = window::close_events().map(Message::WindowClosed);
 
-        let app_key_listener = keyboard::on_key_press(|key, modifiers| match key {
-            key::Key::Named(named) => match named {
-                key::Named::Escape => Some(Message::Cancel),
-                key::Named::Enter => Some(Message::Done),
-                _ => None,
-            },
-            key::Key::Character(char) => match char.as_str() {
-                "s" if modifiers.contains(Modifiers::SHIFT)
-                    && modifiers.contains(Modifiers::ALT) =>
+        let file_drops = event::listen_with(|event, _, id| match event {
+            Event::Window(window::Event::FileDropped(path)) => Some(Message::FileDropped(id, path)),
+            _ => None,
+        });
+
+        let app_key_listener = event::listen_with(|event, status, _| {
+            let Event::Keyboard(keyboard::Event::KeyPressed {
+                key,
+                physical_key,
+                modifiers,
+                ..
+            }) = event
+            else {
+                return None;
+            };
+
+            // Keys used by a focused widget, like a text input, stay with it
+            if status == event::Status::Captured {
+                return None;
+            }
+
+            match &key {
+                key::Key::Named(key::Named::Escape) => Some(Message::Cancel),
+                key::Key::Named(key::Named::Enter) => Some(Message::Done),
+                key::Key::Named(key::Named::Delete | key::Named::Backspace) => {
+                    Some(Message::Delete)
+                }
+                key::Key::Named(
+                    named @ (key::Named::ArrowLeft
+                    | key::Named::ArrowRight
+                    | key::Named::ArrowUp
+                    | key::Named::ArrowDown),
+                ) => {
+                    let step = match modifiers.shift() {
+                        true => 10.0,
+                        false => 1.0,
+                    };
+                    let direction = match named {
+                        key::Named::ArrowLeft => Vector::new(-1.0, 0.0),
+                        key::Named::ArrowRight => Vector::new(1.0, 0.0),
+                        key::Named::ArrowUp => Vector::new(0.0, -1.0),
+                        _ => Vector::new(0.0, 1.0),
+                    };
+
+                    Some(Message::Nudge(direction * step))
+                }
+                key::Key::Character(char)
+                    if char.eq_ignore_ascii_case("c") && modifiers.is_empty() =>
                 {
-                    Some(Message::OpenCaptureWindow)
+                    Some(Message::CopyColor)
                 }
-                "z" if modifiers == Modifiers::CTRL => Some(Message::Undo),
-                _ => None,
-            },
-            _ => None,
+                key::Key::Character(char)
+                    if char.as_str() == "z" && modifiers == Modifiers::CTRL =>
+                {
+                    Some(Message::Undo)
+                }
+                key::Key::Character(char)
+                    if (char.eq_ignore_ascii_case("z")
+                        && modifiers == Modifiers::CTRL | Modifiers::SHIFT)
+                        || (char.eq_ignore_ascii_case("y") && modifiers == Modifiers::CTRL) =>
+                {
+                    Some(Message::Redo)
+                }
+                _ => Chord::from_iced(&physical_key, modifiers).map(Message::HotkeyPressed),
+            }
         });
 
         let global_key_listener = Subscription::run(global_key_listener);
@@ -49,13 +105,21 @@ impl App {
 
         let ipc = Subscription::run(ipc_listener);
 
+        let countdown = if self.countdown.is_some() {
+            time::every(Duration::from_secs(1)).map(|_| Message::CountdownTick)
+        } else {
+            Subscription::none()
+        };
+
         Subscription::batch([
             window_events,
+            file_drops,
             app_key_listener,
             global_key_listener,
             tray_icon_listener,
             tray_menu_listener,
             ipc,
+            countdown,
         ])
     }
 }
@@ -5,6 +5,7 @@ pub mod picklist;
 pub mod scrollable;
 pub mod slider;
 pub mod text;
+pub mod text_editor;
 pub mod text_input;
 pub mod toggler;
 
@@ -1,4 +1,7 @@
-use std::time::Duration;
+use std::{
+    cell::RefCell,
+    time::Duration,
+};
 
 use iced::{
     futures::{
@@ -17,65 +20,201 @@ use tray_icon::{
     TrayIconBuilder,
     TrayIconEvent,
     menu::{
+        IsMenuItem,
         Menu,
         MenuEvent,
         MenuItem,
         PredefinedMenuItem,
-        accelerator::{
-            Accelerator,
-            Code,
-            Modifiers,
-        },
+        Submenu,
     },
 };
 use xcap::image::load_from_memory;
 
 use crate::{
     Message,
+    cli::CaptureTarget,
     consts::{
         APPICON,
         APPNAME,
     },
+    hotkeys::Hotkeys,
 };
 
-pub fn create_tray_icon() -> TrayIcon {
+/// Preset delays offered in the tray menu, in seconds.
+const CAPTURE_DELAYS: [u32; 3] = [3, 5, 10];
+
+/// How often the GTK thread checks for tray changes.
+#[cfg(target_os = "linux")]
+const TRAY_UPDATE_INTERVAL: Duration = Duration::from_millis(250);
+
+thread_local! {
+    /// Tray icon, owned by the thread that created it.
+    static TRAY_ICON: RefCell<Option<TrayIcon>> = const { RefCell::new(None) };
+}
+
+/// Changes sent to the GTK thread, which owns the tray icon on Linux.
+#[cfg(target_os = "linux")]
+enum TrayUpdate {
+    Menu(Hotkeys, u32, bool),
+    Status(Option<String>),
+}
+
+#[cfg(target_os = "linux")]
+static TRAY_UPDATES: std::sync::OnceLock<std::sync::mpsc::Sender<TrayUpdate>> =
+    std::sync::OnceLock::new();
+
+/// Creates the tray icon, kept by the calling thread for later menu changes.
+pub fn create_tray_icon(hotkeys: &Hotkeys, capture_delay: u32) {
     let icon_image = load_from_memory(APPICON).expect("Icon should be loaded");
     let (width, height) = (icon_image.width(), icon_image.height());
 
     let icon =
         Icon::from_rgba(icon_image.into_bytes(), width, height).expect("Icon should be created");
 
-    let menu = Menu::with_items(&[
+    let tray_icon = TrayIconBuilder::new()
+        .with_icon(icon)
+        .with_menu(Box::new(tray_menu(hotkeys, capture_delay, false)))
+        .with_tooltip(default_tooltip())
+        .build()
+        .expect("Tray icon should be created");
+
+    TRAY_ICON.set(Some(tray_icon));
+
+    #[cfg(target_os = "linux")]
+    {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let _ = TRAY_UPDATES.set(sender);
+
+        gtk::glib::timeout_add_local(TRAY_UPDATE_INTERVAL, move || {
+            receiver.try_iter().for_each(|update| match update {
+                TrayUpdate::Menu(hotkeys, capture_delay, counting_down) => {
+                    set_tray_menu(&hotkeys, capture_delay, counting_down)
+                }
+                TrayUpdate::Status(status) => set_tray_status(status),
+            });
+            gtk::glib::ControlFlow::Continue
+        });
+    }
+}
+
+/// Rebuilds the tray menu, so it shows the current hotkeys and capture delay,
+/// and a way to cancel a running countdown.
+pub fn refresh_tray_menu(hotkeys: &Hotkeys, capture_delay: u32, counting_down: bool) {
+    #[cfg(not(target_os = "linux"))]
+    set_tray_menu(hotkeys, capture_delay, counting_down);
+
+    #[cfg(target_os = "linux")]
+    if let Some(sender) = TRAY_UPDATES.get() {
+        let _ = sender.send(TrayUpdate::Menu(
+            hotkeys.clone(),
+            capture_delay,
+            counting_down,
+        ));
+    }
+}
+
+/// Shows a short status on the tray icon, or the default one when `None`.
+pub fn refresh_tray_status(status: Option<String>) {
+    #[cfg(not(target_os = "linux"))]
+    set_tray_status(status);
+
+    #[cfg(target_os = "linux")]
+    if let Some(sender) = TRAY_UPDATES.get() {
+        let _ = sender.send(TrayUpdate::Status(status));
+    }
+}
+
+fn set_tray_menu(hotkeys: &Hotkeys, capture_delay: u32, counting_down: bool) {
+    TRAY_ICON.with_borrow(|tray_icon| {
+        if let Some(tray_icon) = tray_icon {
+            tray_icon.set_menu(Some(Box::new(tray_menu(
+                hotkeys,
+                capture_delay,
+                counting_down,
+            ))));
+        }
+    });
+}
+
+/// Tooltips are not shown on Linux, so the status goes in the label next to the icon there.
+fn set_tray_status(status: Option<String>) {
+    TRAY_ICON.with_borrow(|tray_icon| {
+        if let Some(tray_icon) = tray_icon {
+            #[cfg(not(target_os = "linux"))]
+            let _ = tray_icon.set_tooltip(Some(status.unwrap_or_else(default_tooltip)));
+
+            #[cfg(target_os = "linux")]
+            tray_icon.set_title(status);
+        }
+    });
+}
+
+fn default_tooltip() -> String {
+    format!("{} {}", APPNAME, env!("CARGO_PKG_VERSION"))
+}
+
+fn tray_menu(hotkeys: &Hotkeys, capture_delay: u32, counting_down: bool) -> Menu {
+    let mut delays = CAPTURE_DELAYS.to_vec();
+    if !delays.contains(&capture_delay) {
+        delays.push(capture_delay);
+        delays.sort();
+    }
+
+    let delay_items = delays
+        .into_iter()
+        .map(|seconds| {
+            MenuItem::with_id(
+                format!("capture_after_{seconds}"),
+                format!("{seconds} Seconds"),
+                true,
+                None,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let delay_menu = Submenu::with_items(
+        "Capture After",
+        true,
+        &delay_items
+            .iter()
+            .map(|item| item as &dyn IsMenuItem)
+            .collect::<Vec<_>>(),
+    )
+    .expect("Submenu should be created");
+
+    let cancel_countdown = MenuItem::with_id("cancel_countdown", "Cancel Countdown", true, None);
+    let delayed_capture: &dyn IsMenuItem = match counting_down {
+        true => &cancel_countdown,
+        false => &delay_menu,
+    };
+
+    Menu::with_items(&[
         &MenuItem::with_id(
             "open",
             "Open",
             true,
-            Some(Accelerator::new(
-                Some(Modifiers::SHIFT.union(Modifiers::ALT)),
-                Code::KeyO,
-            )),
+            hotkeys.open_settings.map(|chord| chord.accelerator()),
         ),
         &PredefinedMenuItem::separator(),
         &MenuItem::with_id(
             "capture",
             "Capture",
             true,
-            Some(Accelerator::new(
-                Some(Modifiers::SHIFT.union(Modifiers::ALT)),
-                Code::KeyS,
-            )),
+            hotkeys.capture_area.map(|chord| chord.accelerator()),
         ),
+        delayed_capture,
+        &MenuItem::with_id(
+            "repeat_last_region",
+            "Repeat Last Region",
+            true,
+            hotkeys.repeat_last_region.map(|chord| chord.accelerator()),
+        ),
+        &MenuItem::with_id("capture_each_monitor", "Capture Each Monitor", true, None),
+        &MenuItem::with_id("open_file", "Open File...", true, None),
         &PredefinedMenuItem::separator(),
         &MenuItem::with_id("exit", "Exit", true, None),
     ])
-    .expect("Menu should be created");
-
-    TrayIconBuilder::new()
-        .with_icon(icon)
-        .with_menu(Box::new(menu))
-        .with_tooltip(format!("{} {}", APPNAME, env!("CARGO_PKG_VERSION")))
-        .build()
-        .expect("Tray icon should be created")
+    .expect("Menu should be created")
 }
 
 pub fn tray_icon_listener() -> impl Stream<Item = Message> {
@@ -121,10 +260,38 @@ pub fn tray_menu_listener() -> impl Stream<Item = Message> {
                     sleep(Duration::from_secs(1)).await;
                     let _ = output.send(Message::OpenCaptureWindow).await;
                 }
+                "repeat_last_region" => {
+                    let _ = output
+                        .send(Message::QuickCapture(CaptureTarget::LastRegion))
+                        .await;
+                }
+                "capture_each_monitor" => {
+                    let _ = output
+                        .send(Message::QuickCapture(CaptureTarget::EachMonitor))
+                        .await;
+                }
+                "cancel_countdown" => {
+                    let _ = output.send(Message::CancelCountdown).await;
+                }
+                "open_file" => {
+                    let _ = output.send(Message::PickFile).await;
+                }
                 "exit" => {
                     let _ = output.send(Message::ExitApp).await;
                 }
-                _ => {}
+                id => {
+                    if let Some(seconds) = id
+                        .strip_prefix("capture_after_")
+                        .and_then(|seconds| seconds.parse().ok())
+                    {
+                        let _ = output
+                            .send(Message::StartCountdown(
+                                seconds,
+                                Box::new(Message::OpenCaptureWindow),
+                            ))
+                            .await;
+                    }
+                }
             }
         }
     })
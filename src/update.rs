@@ -1,3 +1,15 @@
+use std::{
+    path::{
+        Path,
+        PathBuf,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+use anyhow::Result;
 use iced::{
     Point,
     Size,
@@ -8,6 +20,8 @@ use iced::{
     },
 };
 use mouse_position::mouse_position::Mouse;
+use rfd::FileDialog;
+use tokio::time::sleep;
 
 use crate::{
     App,
@@ -15,15 +29,50 @@ use crate::{
     capture::{
         self,
         Capture,
+        PROJECT_EXTENSION,
+    },
+    cli::{
+        CaptureArgs,
+        CaptureTarget,
     },
     consts::APPICON,
+    countdown::Countdown,
+    desktop::Desktop,
+    headless,
+    hotkeys::HotkeyAction,
+    ipc::protocol::{
+        Request,
+        Responder,
+        Response,
+    },
     settings::{
         self,
         Settings,
     },
+    tray_icon::{
+        refresh_tray_menu,
+        refresh_tray_status,
+    },
     window::AppWindow,
 };
 
+const HOTKEY_DEBOUNCE: Duration = Duration::from_millis(300);
+
+const COUNTDOWN_CLOSE_DELAY: Duration = Duration::from_millis(200);
+
+/// Edge of the countdown overlay.
+#[cfg(target_os = "linux")]
+const COUNTDOWN_SIZE: f32 = 72.0;
+
+/// Distance between the countdown overlay and the corner of the monitor.
+#[cfg(target_os = "linux")]
+const COUNTDOWN_MARGIN: f32 = 24.0;
+
+const OPENABLE_EXTENSIONS: [&str; 6] = [PROJECT_EXTENSION, "png", "jpg", "jpeg", "webp", "bmp"];
+
+/// Largest part of the monitor an opened image or project may cover.
+const OPENED_IMAGE_MAX_COVERAGE: f32 = 0.9;
+
 impl App {
     pub fn update(&mut self, message: Message) -> Task<Message> {
         match message {
@@ -64,73 +113,263 @@ impl App {
                     return task.discard().chain(window::gain_focus(id));
                 }
             }
-            Message::OpenCaptureWindow => {
-                if self.windows.is_empty()
-                    || !matches!(
-                        self.windows
-                            .first_key_value()
-                            .expect("A window must exist")
-                            .1,
-                        AppWindow::Capture(_)
-                    )
+            Message::OpenCaptureWindow => match self.open_capture_window() {
+                Ok(task) => return task,
+                Err(err) => {
+                    let error = err.to_string();
+                    self.notify(&error, None);
+                }
+            },
+            Message::PickFile => {
+                if let Some(path) = FileDialog::new()
+                    .add_filter("Images and Projects", &OPENABLE_EXTENSIONS)
+                    .add_filter("Capter Project", &[PROJECT_EXTENSION])
+                    .set_directory(&self.config.folder_path)
+                    .pick_file()
                 {
-                    let (x, y) = match Mouse::get_mouse_position() {
-                        Mouse::Position { x, y } => (x, y),
-                        Mouse::Error => (0, 0),
-                    };
-
-                    match xcap::Monitor::from_point(x, y) {
-                        Ok(monitor) => {
-                            let (id, open_task) = window::open(window::Settings {
-                                position: window::Position::Specific(Point::new(
-                                    x as f32, y as f32,
-                                )),
-                                transparent: true,
-                                decorations: false,
-                                #[cfg(target_os = "windows")]
-                                platform_specific: PlatformSpecific {
-                                    drag_and_drop: false,
-                                    skip_taskbar: true,
-                                    undecorated_shadow: false,
-                                },
-                                ..Default::default()
-                            });
-
-                            match Capture::new(monitor) {
-                                Ok(capture) => {
-                                    self.windows.insert(id, capture.into());
-
-                                    return open_task
-                                        .discard()
-                                        .chain(window::gain_focus(id))
-                                        .chain(window::set_mode(id, window::Mode::Fullscreen));
-                                }
-                                Err(err) => {
-                                    let error = err.to_string();
-                                    self.notify(&error, None);
-                                }
-                            };
-                        }
-                        Err(err) => {
-                            let error = err.to_string();
-                            self.notify(&error, None);
-                        }
+                    return Task::done(Message::OpenFile(path));
+                }
+            }
+            Message::OpenFile(path) => match self.open_file_window(&path) {
+                Ok(task) => return task,
+                Err(err) => {
+                    let error = err.to_string();
+                    self.notify(&error, None);
+                }
+            },
+            Message::FileDropped(id, path) => {
+                if let Some(AppWindow::Settings(_)) = self.windows.get(&id) {
+                    return Task::done(Message::OpenFile(path));
+                }
+            }
+            Message::QuickCapture(target) => {
+                return self.capture_headless(
+                    CaptureArgs {
+                        target: Some(target),
+                        output: None,
+                        copy_to_clipboard: true,
+                        delay: 0,
+                    },
+                    None,
+                );
+            }
+            Message::HeadlessCaptured(result, responder) => {
+                let result = result.map_err(anyhow::Error::msg);
+                self.notify_headless(&result);
+                if let Some(responder) = responder {
+                    responder.respond(result.into());
+                }
+            }
+            Message::StartCountdown(0, then) => {
+                return Task::done(*then);
+            }
+            Message::StartCountdown(seconds, then) => {
+                // Only one delayed capture runs at a time
+                if self.countdown.is_some() {
+                    if let Message::Ipc(_, responder) = *then {
+                        responder.respond(Response::Error {
+                            message: "A delayed capture is already in progress".to_string(),
+                        });
                     }
+                    return Task::none();
                 }
+
+                #[cfg(target_os = "linux")]
+                let (overlay, task) = {
+                    let (id, task) = open_countdown_overlay();
+                    self.windows.insert(id, AppWindow::Countdown);
+                    (Some(id), task)
+                };
+                #[cfg(not(target_os = "linux"))]
+                let (overlay, task) = (None, Task::none());
+
+                let countdown = Countdown::new(seconds, *then, overlay);
+                refresh_tray_status(Some(countdown.status()));
+                self.countdown = Some(countdown);
+                self.refresh_tray_menu();
+
+                return task;
+            }
+            Message::CountdownTick => {
+                let Some(countdown) = &mut self.countdown else {
+                    return Task::none();
+                };
+
+                let Some(then) = countdown.tick() else {
+                    refresh_tray_status(Some(countdown.status()));
+                    return Task::none();
+                };
+
+                // Give the overlay time to disappear before anything is captured.
+                return self
+                    .end_countdown()
+                    .chain(Task::perform(sleep(COUNTDOWN_CLOSE_DELAY), move |_| then));
             }
+            Message::CancelCountdown => {
+                if let Some(Message::Ipc(_, responder)) =
+                    self.countdown.as_mut().and_then(Countdown::cancel)
+                {
+                    responder.respond(Response::Error {
+                        message: "Capture cancelled".to_string(),
+                    });
+                }
+                return self.end_countdown();
+            }
+            Message::HotkeyPressed(chord) => {
+                let now = Instant::now();
+
+                // The global and in-app listeners may both see the same press,
+                // and held keys repeat, so ignore the chord until it settles.
+                let is_repeat = self.last_hotkey.is_some_and(|(last_chord, time)| {
+                    last_chord == chord && now.duration_since(time) < HOTKEY_DEBOUNCE
+                });
+
+                self.last_hotkey = Some((chord, now));
+
+                if is_repeat {
+                    return Task::none();
+                }
+
+                if let Some((id, _)) = self.windows.iter().find(|(_, window)| {
+                    matches!(window, AppWindow::Settings(settings) if settings.is_recording_hotkey())
+                }) {
+                    return Task::done(Message::Settings(
+                        *id,
+                        settings::Message::HotkeyRecorded(chord),
+                    ));
+                }
+
+                match self.config.hotkeys.action(chord) {
+                    Some(HotkeyAction::CaptureArea) => {
+                        return Task::done(Message::OpenCaptureWindow);
+                    }
+                    Some(HotkeyAction::DelayedCapture) if self.countdown.is_some() => {
+                        return Task::done(Message::CancelCountdown);
+                    }
+                    Some(HotkeyAction::DelayedCapture) => {
+                        return Task::done(Message::StartCountdown(
+                            self.config.capture_delay,
+                            Box::new(Message::OpenCaptureWindow),
+                        ));
+                    }
+                    Some(HotkeyAction::CaptureFullscreen) => {
+                        return Task::done(Message::QuickCapture(CaptureTarget::FullScreen));
+                    }
+                    Some(HotkeyAction::CaptureWindow) => {
+                        return Task::done(Message::QuickCapture(CaptureTarget::WindowUnderCursor));
+                    }
+                    Some(HotkeyAction::RepeatLastRegion) => {
+                        return Task::done(Message::QuickCapture(CaptureTarget::LastRegion));
+                    }
+                    Some(HotkeyAction::OpenSettings) => {
+                        return Task::done(Message::OpenSettingsWindow);
+                    }
+                    None => {}
+                }
+            }
+            Message::Ipc(request, responder) => match request {
+                Request::OpenCapture { delay } if delay > 0 => {
+                    return Task::done(Message::StartCountdown(
+                        delay,
+                        Box::new(Message::Ipc(Request::OpenCapture { delay: 0 }, responder)),
+                    ));
+                }
+                Request::Capture(args) if args.delay > 0 => {
+                    return Task::done(Message::StartCountdown(
+                        args.delay,
+                        Box::new(Message::Ipc(
+                            Request::Capture(CaptureArgs { delay: 0, ..args }),
+                            responder,
+                        )),
+                    ));
+                }
+                Request::OpenCapture { .. } => match self.open_capture_window() {
+                    Ok(task) => {
+                        self.capture_responders.push(responder);
+                        return task;
+                    }
+                    Err(err) => responder.respond(Response::Error {
+                        message: err.to_string(),
+                    }),
+                },
+                Request::OpenSettings => {
+                    responder.respond(Response::Ok);
+                    return Task::done(Message::OpenSettingsWindow);
+                }
+                Request::OpenFile { path } => {
+                    responder.respond(Response::Ok);
+                    return Task::done(Message::OpenFile(path));
+                }
+                Request::Capture(args) => {
+                    return self.capture_headless(args, Some(responder));
+                }
+                Request::GetConfig => {
+                    responder.respond(match serde_json::to_value(&self.config) {
+                        Ok(config) => Response::Config { config },
+                        Err(err) => Response::Error {
+                            message: err.to_string(),
+                        },
+                    });
+                }
+                Request::SetConfig { key, value } => {
+                    let hotkeys = self.config.hotkeys.clone();
+                    let capture_delay = self.config.capture_delay;
+                    responder.respond(match self.config.set(&key, value) {
+                        Ok(()) => Response::Ok,
+                        Err(err) => Response::Error {
+                            message: err.to_string(),
+                        },
+                    });
+
+                    self.windows.values_mut().for_each(|window| {
+                        if let AppWindow::Settings(settings) = window {
+                            **settings = Settings::init(&self.config);
+                        }
+                    });
+
+                    if self.config.hotkeys != hotkeys || self.config.capture_delay != capture_delay
+                    {
+                        self.refresh_tray_menu();
+                    }
+                }
+                Request::Exit => {
+                    responder.respond(Response::Ok);
+                    return Task::done(Message::ExitApp);
+                }
+            },
             Message::Undo => {
-                if let Some((id, AppWindow::Capture(_))) = self.windows.last_key_value() {
-                    return Task::done(Message::Capture(*id, capture::Message::Undo));
+                if let Some(id) = self.capture_window() {
+                    return Task::done(Message::Capture(id, capture::Message::Undo));
+                }
+            }
+            Message::Redo => {
+                if let Some(id) = self.capture_window() {
+                    return Task::done(Message::Capture(id, capture::Message::Redo));
+                }
+            }
+            Message::Delete => {
+                if let Some(id) = self.capture_window() {
+                    return Task::done(Message::Capture(id, capture::Message::Delete));
+                }
+            }
+            Message::Nudge(offset) => {
+                if let Some(id) = self.capture_window() {
+                    return Task::done(Message::Capture(id, capture::Message::Nudge(offset)));
+                }
+            }
+            Message::CopyColor => {
+                if let Some(id) = self.capture_window() {
+                    return Task::done(Message::Capture(id, capture::Message::CopyColor));
                 }
             }
             Message::Done => {
-                if let Some((id, AppWindow::Capture(_))) = self.windows.last_key_value() {
-                    return Task::done(Message::Capture(*id, capture::Message::Done));
+                if let Some(id) = self.capture_window() {
+                    return Task::done(Message::Capture(id, capture::Message::Done));
                 }
             }
             Message::Cancel => {
-                if let Some((id, AppWindow::Capture(_))) = self.windows.last_key_value() {
-                    return Task::done(Message::Capture(*id, capture::Message::Cancel));
+                if let Some(id) = self.capture_window() {
+                    return Task::done(Message::Capture(id, capture::Message::Cancel));
                 }
             }
             Message::RequestClose(id) => {
@@ -141,19 +380,34 @@ impl App {
                     Some(AppWindow::Settings(_)) => {
                         let _ = self.config.save();
                     }
+                    Some(AppWindow::Countdown) => {}
                     Some(AppWindow::Capture(capture)) => {
+                        let region = capture.region();
                         let result = capture.finalize(&self.config);
 
+                        if result.is_ok()
+                            && let Some(region) = region
+                        {
+                            self.config.last_region = Some(region);
+                            let _ = self.config.save();
+                        }
+
                         let image_path = result
                             .as_ref()
                             .ok()
                             .and_then(|filename| filename.to_str().map(String::from));
 
                         let msg = result
+                            .as_ref()
                             .map(|_| "Screenshot saved and copied to clipboard".to_string())
                             .unwrap_or_else(|err| err.to_string());
 
                         self.notify(&msg, image_path);
+
+                        let response = Response::from(result);
+                        self.capture_responders
+                            .drain(..)
+                            .for_each(|responder| responder.respond(response.clone()));
                     }
                     None => {}
                 };
@@ -164,8 +418,15 @@ impl App {
             }
             Message::Settings(id, message) => {
                 if let Some(AppWindow::Settings(config_window)) = self.windows.get_mut(&id) {
+                    let hotkeys = self.config.hotkeys.clone();
+                    let capture_delay = self.config.capture_delay;
                     let action = config_window.update(message, &mut self.config);
 
+                    if self.config.hotkeys != hotkeys || self.config.capture_delay != capture_delay
+                    {
+                        self.refresh_tray_menu();
+                    }
+
                     let mut tasks = Vec::with_capacity(2);
 
                     tasks.push(
@@ -205,6 +466,10 @@ impl App {
                             capture::Request::Close => {
                                 tasks.push(Task::done(Message::RequestClose(id)));
                             }
+                            capture::Request::SavePalette(palette) => {
+                                self.config.palette = palette;
+                                let _ = self.config.save();
+                            }
                         });
 
                     return Task::batch(tasks);
@@ -213,4 +478,230 @@ impl App {
         }
         Task::none()
     }
+
+    /// Capture window, which shortcuts apply to even while the countdown overlay is open.
+    fn capture_window(&self) -> Option<window::Id> {
+        self.windows
+            .iter()
+            .find_map(|(id, window)| matches!(window, AppWindow::Capture(_)).then_some(*id))
+    }
+
+    /// Stops the countdown, closing its overlay and restoring the tray.
+    fn end_countdown(&mut self) -> Task<Message> {
+        let Some(countdown) = self.countdown.take() else {
+            return Task::none();
+        };
+
+        refresh_tray_status(None);
+        self.refresh_tray_menu();
+
+        countdown.overlay.map_or_else(Task::none, window::close)
+    }
+
+    fn refresh_tray_menu(&self) {
+        refresh_tray_menu(
+            &self.config.hotkeys,
+            self.config.capture_delay,
+            self.countdown.is_some(),
+        );
+    }
+
+    /// Captures without opening the capture window, away from the UI thread.
+    fn capture_headless(&self, args: CaptureArgs, responder: Option<Responder>) -> Task<Message> {
+        let config = self.config.clone();
+
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    headless::capture(&args, &config).map_err(|err| err.to_string())
+                })
+                .await
+                .unwrap_or_else(|err| Err(err.to_string()))
+            },
+            move |result| Message::HeadlessCaptured(result, responder),
+        )
+    }
+
+    /// Notifies the result of a capture made without the capture window.
+    fn notify_headless(&self, result: &Result<Vec<PathBuf>>) {
+        let image_path = result
+            .as_ref()
+            .ok()
+            .and_then(|paths| paths.first())
+            .and_then(|filename| filename.to_str().map(String::from));
+
+        let msg = result
+            .as_ref()
+            .map(|paths| match paths.len() {
+                1 => "Screenshot saved".to_string(),
+                count => format!("{count} Screenshots saved"),
+            })
+            .unwrap_or_else(|err| err.to_string());
+
+        self.notify(&msg, image_path);
+    }
+
+    fn open_capture_window(&mut self) -> Result<Task<Message>> {
+        if self
+            .windows
+            .values()
+            .any(|window| matches!(window, AppWindow::Capture(_)))
+        {
+            return Ok(Task::none());
+        }
+
+        let (x, y) = match Mouse::get_mouse_position() {
+            Mouse::Position { x, y } => (x, y),
+            Mouse::Error => (0, 0),
+        };
+
+        let monitor = xcap::Monitor::from_point(x, y)?;
+
+        if self.config.span_all_monitors {
+            return self.open_desktop_capture_window(monitor.scale_factor()?);
+        }
+
+        let capture = Capture::new(monitor, &self.config)?;
+
+        let (id, open_task) = window::open(window::Settings {
+            position: window::Position::Specific(Point::new(x as f32, y as f32)),
+            transparent: true,
+            decorations: false,
+            #[cfg(target_os = "windows")]
+            platform_specific: PlatformSpecific {
+                drag_and_drop: false,
+                skip_taskbar: true,
+                undecorated_shadow: false,
+            },
+            ..Default::default()
+        });
+
+        self.windows.insert(id, capture.into());
+
+        Ok(open_task
+            .discard()
+            .chain(window::gain_focus(id))
+            .chain(window::set_mode(id, window::Mode::Fullscreen)))
+    }
+
+    /// Opens a borderless capture window covering the whole virtual desktop.
+    ///
+    /// Fullscreen windows are bound to a single monitor, so the window is sized
+    /// and positioned to span every monitor instead.
+    fn open_desktop_capture_window(&mut self, scale_factor: f32) -> Result<Task<Message>> {
+        let desktop = Desktop::capture()?;
+
+        let (origin_x, origin_y) = desktop.origin;
+        let position = Point::new(
+            origin_x as f32 * desktop.density / scale_factor,
+            origin_y as f32 * desktop.density / scale_factor,
+        );
+        let size = Size::new(
+            desktop.screenshot.width() as f32 / scale_factor,
+            desktop.screenshot.height() as f32 / scale_factor,
+        );
+
+        let capture = Capture::new_desktop(desktop, scale_factor, &self.config);
+
+        let (id, open_task) = window::open(window::Settings {
+            size,
+            position: window::Position::Specific(position),
+            resizable: false,
+            transparent: true,
+            decorations: false,
+            level: window::Level::AlwaysOnTop,
+            #[cfg(target_os = "windows")]
+            platform_specific: PlatformSpecific {
+                drag_and_drop: false,
+                skip_taskbar: true,
+                undecorated_shadow: false,
+            },
+            ..Default::default()
+        });
+
+        self.windows.insert(id, capture.into());
+
+        Ok(open_task.discard().chain(window::gain_focus(id)))
+    }
+
+    /// Opens a project or an image in a regular window sized to its screenshot.
+    fn open_file_window(&mut self, path: &Path) -> Result<Task<Message>> {
+        if self
+            .windows
+            .values()
+            .any(|window| matches!(window, AppWindow::Capture(_)))
+        {
+            return Ok(Task::none());
+        }
+
+        let (x, y) = match Mouse::get_mouse_position() {
+            Mouse::Position { x, y } => (x, y),
+            Mouse::Error => (0, 0),
+        };
+
+        let monitor = xcap::Monitor::from_point(x, y)?;
+        let scale_factor = monitor.scale_factor()?;
+
+        let max_size = Size::new(
+            monitor.width()? as f32 / scale_factor,
+            monitor.height()? as f32 / scale_factor,
+        ) * OPENED_IMAGE_MAX_COVERAGE;
+
+        let capture = if path
+            .extension()
+            .is_some_and(|extension| extension == PROJECT_EXTENSION)
+        {
+            Capture::from_project(path, max_size, &self.config)?
+        } else {
+            Capture::from_image(path, max_size, scale_factor, &self.config)?
+        };
+
+        let (id, open_task) = window::open(window::Settings {
+            size: capture.window_size(),
+            position: window::Position::Centered,
+            resizable: false,
+            ..Default::default()
+        });
+
+        self.windows.insert(id, capture.into());
+
+        Ok(open_task.discard().chain(window::gain_focus(id)))
+    }
+}
+
+/// Opens the countdown overlay in the top right corner of the monitor under the cursor.
+///
+/// Being override-redirect it never takes focus, and clicks go through it to the windows below.
+#[cfg(target_os = "linux")]
+fn open_countdown_overlay() -> (window::Id, Task<Message>) {
+    let position = match Mouse::get_mouse_position() {
+        Mouse::Position { x, y } => xcap::Monitor::from_point(x, y).ok().and_then(|monitor| {
+            let scale_factor = monitor.scale_factor().ok()?;
+            let right = monitor.x().ok()? + monitor.width().ok()? as i32;
+
+            Some(Point::new(
+                right as f32 / scale_factor - COUNTDOWN_SIZE - COUNTDOWN_MARGIN,
+                monitor.y().ok()? as f32 / scale_factor + COUNTDOWN_MARGIN,
+            ))
+        }),
+        Mouse::Error => None,
+    };
+
+    let (id, task) = window::open(window::Settings {
+        size: Size::new(COUNTDOWN_SIZE, COUNTDOWN_SIZE),
+        position: position.map_or(window::Position::Centered, window::Position::Specific),
+        resizable: false,
+        decorations: false,
+        level: window::Level::AlwaysOnTop,
+        platform_specific: PlatformSpecific {
+            application_id: String::from("Capter"),
+            override_redirect: true,
+        },
+        ..Default::default()
+    });
+
+    (
+        id,
+        task.discard().chain(window::enable_mouse_passthrough(id)),
+    )
 }
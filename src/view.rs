@@ -3,12 +3,14 @@ use iced::{
         Base,
         Style,
     },
+    widget::Space,
     window::Id,
 };
 
 use crate::{
     App,
     Message,
+    countdown::Countdown,
     theme::{
         Element,
         Theme,
@@ -37,6 +39,10 @@ impl App {
             Some(AppWindow::Capture(capture)) => capture
                 .view()
                 .map(move |message| Message::Capture(id, message)),
+            Some(AppWindow::Countdown) => self
+                .countdown
+                .as_ref()
+                .map_or_else(|| Space::new().into(), Countdown::view),
             None => unreachable!(),
         }
     }
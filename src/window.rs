@@ -6,6 +6,8 @@ use crate::{
 pub enum AppWindow {
     Settings(Box<Settings>),
     Capture(Box<Capture>),
+    /// Overlay showing the running countdown.
+    Countdown,
 }
 
 impl From<Settings> for AppWindow {